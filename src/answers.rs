@@ -0,0 +1,166 @@
+use std::{collections::BTreeMap, fmt, io, path::Path};
+
+use crate::{
+    runner::Status,
+    solution::{Answer, Part},
+};
+
+pub const DEFAULT_ANSWERS_PATH: &str = "answers.txt";
+
+const HEADER: &str = "\
+# Known answers for the personal puzzle inputs, as `<day> <part> <answer>`.
+# Checked with `cargo run --release -- check`; add new ones with `check --record`.
+# `check --record` rewrites this file sorted by day and part, without any other comments.
+";
+
+/// The expected answers for each day and part, kept as the text they were recorded with.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Answers {
+    answers: BTreeMap<(u8, Part), String>,
+}
+
+impl Answers {
+    pub fn parse(input: &str) -> Result<Answers, String> {
+        let mut answers = Answers::default();
+        for (i, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let (day, part, answer) = match fields[..] {
+                [day, part, answer] => (day.parse::<u8>(), part, answer),
+                _ => return Err(format!("line {}: expected '<day> <part> <answer>'", i + 1)),
+            };
+            let day = day.map_err(|_| format!("line {}: invalid day '{}'", i + 1, fields[0]))?;
+            let part = match part {
+                "1" => Part::One,
+                "2" => Part::Two,
+                _ => return Err(format!("line {}: invalid part '{part}'", i + 1)),
+            };
+            answers.answers.insert((day, part), answer.to_owned());
+        }
+        Ok(answers)
+    }
+
+    /// Loads the answers file, treating a missing file as having no answers.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Answers> {
+        match std::fs::read_to_string(path) {
+            Ok(input) => {
+                Answers::parse(&input).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Writes every answer back out, sorted by day and part under the standard header. The file is
+    /// owned by this: comments and ordering of a hand-edited file are not kept.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, self.to_string())
+    }
+
+    pub fn get(&self, day: u8, part: Part) -> Option<&str> {
+        self.answers.get(&(day, part)).map(String::as_str)
+    }
+
+    pub fn check(&self, day: u8, part: Part, answer: &Answer) -> Status {
+        match self.get(day, part) {
+            Some(expected) if expected == answer.to_string() => Status::Pass,
+            Some(expected) => Status::Fail {
+                expected: expected.to_owned(),
+            },
+            None => Status::Unknown,
+        }
+    }
+
+    /// Records an answer if there isn't one already, returning whether it was added.
+    pub fn record(&mut self, day: u8, part: Part, answer: &Answer) -> bool {
+        let mut added = false;
+        self.answers.entry((day, part)).or_insert_with(|| {
+            added = true;
+            answer.to_string()
+        });
+        added
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(HEADER)?;
+        for ((day, part), answer) in &self.answers {
+            writeln!(f, "{day} {} {answer}", part.number())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{input::personal_input, registry::DAYS};
+
+    #[test]
+    fn test() {
+        let mut answers = Answers::parse(HEADER).unwrap();
+        assert_eq!(answers, Answers::default());
+
+        assert!(answers.record(10, Part::Two, &Answer::Usize(297)));
+        assert!(answers.record(8, Part::Two, &Answer::U64(16342438708751)));
+        assert!(!answers.record(10, Part::Two, &Answer::Usize(298)));
+
+        assert_eq!(
+            answers.check(10, Part::Two, &Answer::Usize(297)),
+            Status::Pass
+        );
+        assert_eq!(
+            answers.check(10, Part::Two, &Answer::Usize(298)),
+            Status::Fail {
+                expected: "297".to_owned()
+            }
+        );
+        assert_eq!(
+            answers.check(10, Part::One, &Answer::Usize(297)),
+            Status::Unknown
+        );
+        assert_eq!(Answers::parse(&answers.to_string()).unwrap(), answers);
+
+        assert!(Answers::parse("8 3 10").is_err());
+        assert!(Answers::parse("eight 1 10").is_err());
+        assert!(Answers::parse("8 1").is_err());
+    }
+
+    #[test]
+    fn test_my_inputs() {
+        let answers = Answers::load(DEFAULT_ANSWERS_PATH).unwrap();
+        let mut skipped = Vec::new();
+        for day in DAYS {
+            let parts = Part::ALL.map(|part| (part, answers.get(day.number, part)));
+            if parts.iter().all(|(_, expected)| expected.is_none()) {
+                continue;
+            }
+            let Some(input) = personal_input(day.number) else {
+                skipped.push(day.number);
+                continue;
+            };
+
+            let parsed = day.parse(&input).unwrap();
+            for (part, expected) in parts {
+                if let Some(expected) = expected {
+                    assert_eq!(
+                        parsed.part(part).unwrap().to_string(),
+                        expected,
+                        "day {} part {}",
+                        day.number,
+                        part.number()
+                    );
+                }
+            }
+        }
+
+        if !skipped.is_empty() {
+            eprintln!("answers not checked for days {skipped:?}: no personal input");
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use std::{
+    fmt,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use crate::solution::{Day, Error, Part};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchConfig {
+    /// Untimed runs of each stage before measuring, to warm caches and the allocator.
+    pub warmup: usize,
+    pub iterations: usize,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        BenchConfig {
+            warmup: 3,
+            iterations: 20,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &mut [Duration]) -> Stats {
+        assert!(!samples.is_empty(), "no samples to summarise");
+        samples.sort();
+
+        let n = samples.len();
+        let median = if n.is_multiple_of(2) {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2
+        } else {
+            samples[n / 2]
+        };
+
+        let secs = samples.iter().map(Duration::as_secs_f64);
+        let mean = secs.clone().sum::<f64>() / n as f64;
+        let variance = if n > 1 {
+            secs.map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1) as f64
+        } else {
+            0.0
+        };
+
+        Stats {
+            min: samples[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>12.2?} {:>12.2?} {:>12.2?} {:>12.2?}",
+            self.min, self.median, self.mean, self.stddev
+        )
+    }
+}
+
+pub struct DayBench {
+    pub day: u8,
+    pub input_generator: Stats,
+    pub part_1: Option<Stats>,
+    pub part_2: Option<Stats>,
+}
+
+impl fmt::Display for DayBench {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "day {:<13} {:>12} {:>12} {:>12} {:>12}",
+            self.day, "min", "median", "mean", "stddev"
+        )?;
+        writeln!(f, "  {:<15} {}", "input_generator", self.input_generator)?;
+        for (name, stats) in [("part_1", &self.part_1), ("part_2", &self.part_2)] {
+            if let Some(stats) = stats {
+                writeln!(f, "  {name:<15} {stats}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn measure<T>(config: &BenchConfig, mut f: impl FnMut() -> T) -> Stats {
+    for _ in 0..config.warmup {
+        black_box(f());
+    }
+
+    let mut samples = (0..config.iterations.max(1))
+        .map(|_| {
+            let instant = Instant::now();
+            let out = black_box(f());
+            let elapsed = instant.elapsed();
+            drop(out);
+            elapsed
+        })
+        .collect::<Vec<_>>();
+
+    Stats::from_samples(&mut samples)
+}
+
+/// Times each stage of a day separately, reusing one parsed input for both parts.
+/// Each stage is run once up front, so that an input it rejects is reported instead of timed.
+pub fn bench_day(
+    day: &Day,
+    input: &str,
+    config: &BenchConfig,
+    part_1: bool,
+    part_2: bool,
+) -> Result<DayBench, Error> {
+    let parsed = day.parse(input)?;
+    let input_generator = measure(config, || day.parse(black_box(input)));
+
+    let bench_part = |enabled: bool, part: Part| -> Result<Option<Stats>, Error> {
+        if !enabled {
+            return Ok(None);
+        }
+        parsed.part(part)?;
+        Ok(Some(measure(config, || parsed.part(part))))
+    };
+    Ok(DayBench {
+        day: day.number,
+        input_generator,
+        part_1: bench_part(part_1, Part::One)?,
+        part_2: bench_part(part_2, Part::Two)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let ms = Duration::from_millis;
+        let stats = Stats::from_samples(&mut [ms(4), ms(2), ms(8), ms(6)]);
+        assert_eq!(stats.min, ms(2));
+        assert_eq!(stats.median, ms(5));
+        assert_eq!(stats.mean, ms(5));
+        assert_eq!(stats.stddev.as_micros(), 2581);
+
+        let stats = Stats::from_samples(&mut [ms(3)]);
+        assert_eq!(stats.median, ms(3));
+        assert_eq!(stats.stddev, Duration::ZERO);
+
+        let bench = bench_day(
+            crate::registry::find(1).unwrap(),
+            "1abc2\ntreb7uchet\n",
+            &BenchConfig {
+                warmup: 1,
+                iterations: 5,
+            },
+            true,
+            false,
+        )
+        .unwrap();
+        assert!(bench.part_1.is_some());
+        assert!(bench.part_2.is_none());
+    }
+}
@@ -1,63 +1,251 @@
-use std::time::Instant;
+use std::{io, process::ExitCode, time::Instant};
 
-use advent_of_code_2023::*;
+use advent_of_code_2023::{
+    answers::Answers,
+    bench::{self, BenchConfig},
+    cli::{self, CalibrationConfig, CheckConfig, Command, NewDayConfig, Options, Part},
+    day_01,
+    input::{self, InputProvider},
+    registry::DAYS,
+    report::{Format, Reporter},
+    runner::{self, Record, Status},
+    scaffold::{CurlFetcher, InputFetcher, Scaffold},
+    solution::Day,
+};
 
-#[cfg(feature = "io")]
-macro_rules! input_str {
-    ($d:expr) => {
-        std::fs::read_to_string(concat!("input/2023/day", $d, ".txt")).unwrap()
+fn bench_day(
+    day: &Day,
+    provider: &dyn InputProvider,
+    options: &Options,
+    config: &BenchConfig,
+) -> bool {
+    let input = match runner::load_input(day, provider) {
+        Ok(input) => input,
+        Err(status) => {
+            eprintln!(
+                "day {}: {}",
+                day.number,
+                status.detail().unwrap_or_default()
+            );
+            return !status.is_failure();
+        }
     };
+
+    let result = runner::catch_panic(|| {
+        bench::bench_day(
+            day,
+            &input,
+            config,
+            options.runs_part(Part::One),
+            options.runs_part(Part::Two),
+        )
+    });
+    match result {
+        Ok(Ok(result)) => {
+            println!("{result}");
+            true
+        }
+        Ok(Err(e)) => {
+            eprintln!("{e}\n");
+            false
+        }
+        Err(e) => {
+            eprintln!("day {}: {e}\n", day.number);
+            false
+        }
+    }
 }
 
-#[cfg(not(feature = "io"))]
-macro_rules! input_str {
-    ($d:expr) => {
-        include_str!(concat!("../../input/2023/day", $d, ".txt"))
+/// Compares a solved record with the expected answer, recording the answer if asked to and it is new.
+fn check_record(record: &mut Record, answers: &mut Answers, config: &CheckConfig) {
+    let Some(answer) = &record.answer else {
+        return;
     };
+    record.status = answers.check(record.day, record.part, answer);
+    if config.record && answers.record(record.day, record.part, answer) {
+        record.status = Status::Recorded;
+    }
 }
 
-macro_rules! run_parts {
-    ($m:ident, $d:expr, $g:expr) => {
-        let instant = Instant::now();
-        let input = input_str!($d);
-        let processed_input = $g(&input);
-        println!(
-            "day {0}-1: {1}\nday {0}-2: {2}",
-            $d,
-            $m::part_1(&processed_input),
-            $m::part_2(&processed_input)
-        );
+/// How many of the days that were run failed, and how many couldn't be run at all.
+#[derive(Debug, Default)]
+struct Tally {
+    failures: usize,
+    skipped: usize,
+}
 
-        println!("{:?}\n", instant.elapsed());
-    };
+fn report(
+    days: &[&Day],
+    provider: &dyn InputProvider,
+    options: &Options,
+    mut check: Option<(&CheckConfig, &mut Answers)>,
+) -> io::Result<Tally> {
+    let parts = Part::ALL
+        .into_iter()
+        .filter(|p| options.runs_part(*p))
+        .collect::<Vec<_>>();
+
+    let mut reporter = Reporter::new(io::stdout().lock(), options.format)?;
+    let mut tally = Tally::default();
+    for day in days {
+        let mut records = runner::run_day(day, provider, &parts);
+        for record in &mut records {
+            if let Some((config, answers)) = &mut check {
+                check_record(record, answers, config);
+            }
+            reporter.record(record)?;
+        }
+        if records.iter().any(|r| r.status.is_failure()) {
+            tally.failures += 1;
+        } else if records
+            .iter()
+            .all(|r| matches!(r.status, Status::Skipped(_)))
+        {
+            tally.skipped += 1;
+        }
+    }
+    drop(reporter.finish()?);
+
+    Ok(tally)
 }
 
-macro_rules! run_day_with_generator {
-    ($m:ident, $d:expr) => {
-        run_parts!($m, $d, |i| $m::input_generator(i));
+fn calibration(provider: &dyn InputProvider, config: &CalibrationConfig) -> ExitCode {
+    let input = match provider.input(1) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
     };
+    let mut lexicon = config.lexicon.lexicon();
+    if config.ignore_case {
+        lexicon = lexicon.case_insensitive();
+    }
+
+    if config.rewrite {
+        print!("{}", day_01::rewrite(&input, &lexicon));
+        return ExitCode::SUCCESS;
+    }
+    let mut total = 0u64;
+    let mut undecoded = 0;
+    for report in day_01::span_report(&input, &lexicon) {
+        print!("{report}");
+        match report.value {
+            Some(value) => total += value,
+            None => undecoded += 1,
+        }
+    }
+    println!("total {total}, {undecoded} line(s) without numbers");
+    ExitCode::SUCCESS
 }
 
-macro_rules! run_day {
-    ($m:ident, $d:expr) => {
-        run_parts!($m, $d, |i| i);
-    };
+fn new_day(config: &NewDayConfig) -> ExitCode {
+    let fetcher = CurlFetcher::new(&config.cookie);
+    let fetcher = config.fetch.then_some(&fetcher as &dyn InputFetcher);
+    match Scaffold::new(env!("CARGO_MANIFEST_DIR")).new_day(config.day, fetcher) {
+        Ok(changed) => {
+            for path in changed {
+                println!("wrote {}", path.display());
+            }
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: failed to create day {}: {e}", config.day);
+            ExitCode::FAILURE
+        }
+    }
 }
 
-pub fn main() {
+pub fn main() -> ExitCode {
+    let command = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(Command::Help) => {
+            print!("{}", cli::USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Ok(command) => command,
+        Err(e) => {
+            eprint!("error: {e}\n\n{}", cli::USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    let options = match &command {
+        Command::Run(options)
+        | Command::Bench(options, _)
+        | Command::Check(options, _)
+        | Command::Calibration(options, _) => options,
+        Command::NewDay(config) => return new_day(config),
+        Command::Help => unreachable!("help is handled above"),
+    };
+
+    let days = DAYS
+        .iter()
+        .filter(|day| options.days.contains(&day.number))
+        .collect::<Vec<_>>();
+    if days.is_empty() {
+        eprintln!(
+            "error: no days registered between {} and {}",
+            options.days.start(),
+            options.days.end()
+        );
+        return ExitCode::from(2);
+    }
+
+    let provider = input::provider(&options.input);
+    if let Command::Calibration(_, config) = &command {
+        return calibration(provider.as_ref(), config);
+    }
     let instant = Instant::now();
-    run_day!(day_01, "1");
-    run_day_with_generator!(day_02, "2");
-    run_day!(day_03, "3");
-    run_day_with_generator!(day_04, "4");
-    run_day_with_generator!(day_05, "5");
-    run_day_with_generator!(day_06, "6");
-    run_day_with_generator!(day_07, "7");
-    run_day_with_generator!(day_08, "8");
-    run_day_with_generator!(day_09, "9");
-    run_day_with_generator!(day_10, "10");
-    run_day_with_generator!(day_11, "11");
-    run_day_with_generator!(day_12, "12");
-
-    println!("done in {:?}", instant.elapsed());
+    let tally = match &command {
+        Command::Bench(_, config) => Ok(Tally {
+            failures: days
+                .iter()
+                .filter(|day| !bench_day(day, provider.as_ref(), options, config))
+                .count(),
+            skipped: 0,
+        }),
+        Command::Check(_, config) => {
+            let mut answers = match Answers::load(&config.answers) {
+                Ok(answers) => answers,
+                Err(e) => {
+                    eprintln!("error: failed to load {}: {e}", config.answers.display());
+                    return ExitCode::FAILURE;
+                }
+            };
+            let tally = report(
+                &days,
+                provider.as_ref(),
+                options,
+                Some((config, &mut answers)),
+            );
+            if config.record {
+                if let Err(e) = answers.save(&config.answers) {
+                    eprintln!("error: failed to write {}: {e}", config.answers.display());
+                    return ExitCode::FAILURE;
+                }
+            }
+            tally
+        }
+        _ => report(&days, provider.as_ref(), options, None),
+    };
+
+    let Tally { failures, skipped } = match tally {
+        Ok(tally) => tally,
+        Err(e) => {
+            eprintln!("error: failed to write output: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if options.format == Format::Text {
+        println!("done in {:?}", instant.elapsed());
+    }
+    if skipped > 0 {
+        eprintln!("{skipped} day(s) skipped");
+    }
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        eprintln!("{failures} day(s) failed");
+        ExitCode::FAILURE
+    }
 }
@@ -0,0 +1,113 @@
+use std::ops::{BitAnd, BitOr};
+
+/// A set of small numbers, below `64 * WORDS`, stored one bit each.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BitSet<const WORDS: usize = 2> {
+    words: [u64; WORDS],
+}
+
+impl<const WORDS: usize> Default for BitSet<WORDS> {
+    fn default() -> Self {
+        BitSet { words: [0; WORDS] }
+    }
+}
+
+impl<const WORDS: usize> BitSet<WORDS> {
+    /// One more than the largest number the set can hold.
+    pub const CAPACITY: usize = 64 * WORDS;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `n`, returning whether it was newly added.
+    ///
+    /// Panics if `n` is not below [`Self::CAPACITY`].
+    pub fn insert(&mut self, n: usize) -> bool {
+        assert!(
+            n < Self::CAPACITY,
+            "{n} doesn't fit in a {WORDS}-word bit set"
+        );
+        let (word, bit) = (n / 64, 1 << (n % 64));
+        let added = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        added
+    }
+
+    pub fn contains(&self, n: usize) -> bool {
+        n < Self::CAPACITY && self.words[n / 64] & (1 << (n % 64)) != 0
+    }
+
+    pub fn len(&self) -> u32 {
+        self.words.iter().map(|word| word.count_ones()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    /// The numbers in the set, smallest first.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..WORDS).flat_map(move |i| {
+            let mut word = self.words[i];
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    i * 64 + bit
+                })
+            })
+        })
+    }
+}
+
+impl<const WORDS: usize> BitAnd for BitSet<WORDS> {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        BitSet {
+            words: std::array::from_fn(|i| self.words[i] & other.words[i]),
+        }
+    }
+}
+
+impl<const WORDS: usize> BitOr for BitSet<WORDS> {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        BitSet {
+            words: std::array::from_fn(|i| self.words[i] | other.words[i]),
+        }
+    }
+}
+
+impl<const WORDS: usize> FromIterator<usize> for BitSet<WORDS> {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for n in iter {
+            set.insert(n);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let a = [3, 64, 127, 5].into_iter().collect::<BitSet>();
+        let b = [5, 127, 0].into_iter().collect::<BitSet>();
+        assert_eq!(a.len(), 4);
+        assert!(a.contains(64) && !a.contains(63) && !a.contains(500));
+        assert_eq!((a & b).iter().collect::<Vec<_>>(), [5, 127]);
+        assert_eq!((a | b).len(), 5);
+        assert!((a & BitSet::new()).is_empty());
+
+        let mut wide = BitSet::<4>::new();
+        assert!(wide.insert(255));
+        assert!(!wide.insert(255));
+        assert_eq!(BitSet::<4>::CAPACITY, 256);
+    }
+}
@@ -0,0 +1,416 @@
+use std::{fmt, ops::RangeInclusive, path::PathBuf};
+
+use crate::{
+    answers::DEFAULT_ANSWERS_PATH, bench::BenchConfig, day_01::Preset, report::Format,
+    scaffold::DEFAULT_COOKIE_PATH,
+};
+pub use crate::{input::InputSource, solution::Part};
+
+pub const USAGE: &str = "\
+usage: bin [command] [options]
+
+commands:
+  run                     solve the selected days (the default)
+  bench                   time each stage of the selected days over repeated runs
+  check                   compare the answers of the selected days with the answers file
+  calibration             show how each line of a day 1 calibration document decodes
+  new-day <N>             create and register a module for day N from the template
+
+options:
+  -d, --day <N>           run a single day
+      --days <A-B>        run an inclusive range of days
+  -p, --part <1|2>        run only one part
+  -i, --input <PATH>      read input from PATH, or from stdin if PATH is '-' (single day only)
+      --input-dir <DIR>   read day{N}.txt from DIR (defaults to $AOC_INPUT_DIR, then input/2023)
+  -f, --format <FMT>      output format for run and check: text, json or csv (default text)
+  -h, --help              print this message
+
+bench options:
+      --iterations <N>    number of timed runs of each stage (default 20)
+      --warmup <N>        number of untimed runs before timing (default 3)
+
+check options:
+      --answers <PATH>    answers file to check against (default answers.txt)
+      --record            write answers that aren't in the answers file yet back to it
+
+calibration options:
+      --rewrite           print the document with number words replaced by digits instead
+      --lexicon <NAME>    numbers to recognise: digits or english (default english)
+      --ignore-case       match number words regardless of case
+
+new-day options:
+      --fetch             download the day's input into input/2023 with curl
+      --cookie <PATH>     file holding the session cookie for --fetch (default cookies)
+";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    pub days: RangeInclusive<u8>,
+    pub part: Option<Part>,
+    pub input: InputSource,
+    pub format: Format,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            days: 1..=25,
+            part: None,
+            input: InputSource::Default,
+            format: Format::Text,
+        }
+    }
+}
+
+impl Options {
+    pub fn runs_part(&self, part: Part) -> bool {
+        self.part.is_none_or(|p| p == part)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Run(Options),
+    Bench(Options, BenchConfig),
+    Check(Options, CheckConfig),
+    /// Always runs day 1 only.
+    Calibration(Options, CalibrationConfig),
+    NewDay(NewDayConfig),
+    Help,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckConfig {
+    pub answers: PathBuf,
+    /// Write answers for day/parts that don't have one yet back to the answers file.
+    pub record: bool,
+}
+
+impl Default for CheckConfig {
+    fn default() -> Self {
+        CheckConfig {
+            answers: DEFAULT_ANSWERS_PATH.into(),
+            record: false,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CalibrationConfig {
+    /// Print the rewritten document rather than a report of every line.
+    pub rewrite: bool,
+    pub lexicon: Preset,
+    pub ignore_case: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewDayConfig {
+    pub day: u8,
+    /// Download the day's input if there isn't one yet.
+    pub fetch: bool,
+    pub cookie: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsageError(String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for UsageError {}
+
+fn usage_error<T>(msg: impl Into<String>) -> Result<T, UsageError> {
+    Err(UsageError(msg.into()))
+}
+
+fn parse_day(s: &str) -> Result<u8, UsageError> {
+    match s.parse() {
+        Ok(day @ 1..=25) => Ok(day),
+        _ => usage_error(format!("invalid day '{s}', expected a number from 1 to 25")),
+    }
+}
+
+fn parse_days(s: &str) -> Result<RangeInclusive<u8>, UsageError> {
+    let Some((start, end)) = s.split_once('-') else {
+        return usage_error(format!("invalid day range '{s}', expected A-B"));
+    };
+    let (start, end) = (parse_day(start)?, parse_day(end)?);
+    if start > end {
+        return usage_error(format!("invalid day range '{s}', {start} is after {end}"));
+    }
+    Ok(start..=end)
+}
+
+fn parse_count(flag: &str, s: &str) -> Result<usize, UsageError> {
+    s.parse().or_else(|_| {
+        usage_error(format!(
+            "invalid value '{s}' for '{flag}', expected a number"
+        ))
+    })
+}
+
+fn parse_part(s: &str) -> Result<Part, UsageError> {
+    match s {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => usage_error(format!("invalid part '{s}', expected 1 or 2")),
+    }
+}
+
+fn parse_new_day(mut args: impl Iterator<Item = String>) -> Result<Command, UsageError> {
+    let mut day = None;
+    let mut fetch = false;
+    let mut cookie = PathBuf::from(DEFAULT_COOKIE_PATH);
+    while let Some(arg) = args.next() {
+        match arg.split_once('=') {
+            Some(("--cookie", path)) => cookie = path.into(),
+            _ => match arg.as_str() {
+                "-h" | "--help" => return Ok(Command::Help),
+                "--fetch" => fetch = true,
+                "--cookie" => match args.next() {
+                    Some(path) => cookie = path.into(),
+                    None => return usage_error("missing value for '--cookie'"),
+                },
+                _ if arg.starts_with('-') || day.is_some() => {
+                    return usage_error(format!("unrecognised argument '{arg}'"))
+                }
+                _ => day = Some(parse_day(&arg)?),
+            },
+        }
+    }
+
+    match day {
+        Some(day) => Ok(Command::NewDay(NewDayConfig { day, fetch, cookie })),
+        None => usage_error("new-day needs the number of the day to create"),
+    }
+}
+
+/// Parses the command-line arguments, excluding the program name.
+pub fn parse_args<I, S>(args: I) -> Result<Command, UsageError>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let mut args = args.into_iter().map(Into::into).peekable();
+    if args.next_if(|arg| arg == "new-day").is_some() {
+        return parse_new_day(args);
+    }
+    let mut command = match args.peek().map(String::as_str) {
+        Some("run") => Command::Run(Options::default()),
+        Some("bench") => Command::Bench(Options::default(), BenchConfig::default()),
+        Some("check") => Command::Check(Options::default(), CheckConfig::default()),
+        Some("calibration") => Command::Calibration(
+            Options {
+                days: 1..=1,
+                ..Options::default()
+            },
+            CalibrationConfig::default(),
+        ),
+        _ => Command::Run(Options::default()),
+    };
+    if matches!(
+        args.peek().map(String::as_str),
+        Some("run" | "bench" | "check" | "calibration")
+    ) {
+        args.next();
+    }
+
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => {
+                (flag.to_owned(), Some(value.to_owned()))
+            }
+            _ => (arg, None),
+        };
+        let mut value = || match inline_value.clone().or_else(|| args.next()) {
+            Some(value) => Ok(value),
+            None => usage_error(format!("missing value for '{flag}'")),
+        };
+        let only_for = |command: &str| {
+            usage_error(format!("'{flag}' is only valid for the {command} command"))
+        };
+
+        let options = match &mut command {
+            Command::Run(options)
+            | Command::Bench(options, _)
+            | Command::Check(options, _)
+            | Command::Calibration(options, _) => options,
+            Command::NewDay(_) | Command::Help => {
+                unreachable!("new-day and help are returned as soon as they are seen")
+            }
+        };
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-d" | "--day" => {
+                let day = parse_day(&value()?)?;
+                options.days = day..=day;
+            }
+            "--days" => options.days = parse_days(&value()?)?,
+            "-p" | "--part" => options.part = Some(parse_part(&value()?)?),
+            "-i" | "--input" => {
+                options.input = match value()?.as_str() {
+                    "-" => InputSource::Stdin,
+                    path => InputSource::Path(path.into()),
+                }
+            }
+            "--input-dir" => options.input = InputSource::Dir(value()?.into()),
+            "-f" | "--format" => options.format = value()?.parse().map_err(UsageError)?,
+            "--iterations" | "--warmup" => {
+                let Command::Bench(_, config) = &mut command else {
+                    return only_for("bench");
+                };
+                let count = parse_count(&flag, &value()?)?;
+                match flag.as_str() {
+                    "--iterations" => config.iterations = count.max(1),
+                    _ => config.warmup = count,
+                }
+            }
+            "--answers" | "--record" => {
+                let Command::Check(_, config) = &mut command else {
+                    return only_for("check");
+                };
+                match flag.as_str() {
+                    "--answers" => config.answers = value()?.into(),
+                    _ => config.record = true,
+                }
+            }
+            "--rewrite" | "--lexicon" | "--ignore-case" => {
+                let Command::Calibration(_, config) = &mut command else {
+                    return only_for("calibration");
+                };
+                match flag.as_str() {
+                    "--rewrite" => config.rewrite = true,
+                    "--lexicon" => config.lexicon = value()?.parse().map_err(UsageError)?,
+                    _ => config.ignore_case = true,
+                }
+            }
+            _ => return usage_error(format!("unrecognised argument '{flag}'")),
+        }
+    }
+
+    if let Command::Run(options) | Command::Bench(options, _) | Command::Check(options, _) =
+        &command
+    {
+        if matches!(options.input, InputSource::Path(_) | InputSource::Stdin)
+            && options.days.start() != options.days.end()
+        {
+            return usage_error("--input can only be used when running a single day");
+        }
+    }
+    if let Command::Bench(options, _) = &command {
+        if options.format != Format::Text {
+            return usage_error("bench only supports text output");
+        }
+    }
+    if let Command::Calibration(options, _) = &command {
+        if options.days != (1..=1) || options.part.is_some() {
+            return usage_error("calibration always decodes both parts of day 1");
+        }
+        if options.format != Format::Text {
+            return usage_error("calibration only supports text output");
+        }
+    }
+
+    Ok(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(args: &[&str]) -> Options {
+        match parse_args(args.iter().copied()) {
+            Ok(Command::Run(options)) => options,
+            other => panic!("expected run options, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test() {
+        assert_eq!(run(&[]), Options::default());
+        assert_eq!(run(&["--day", "5"]).days, 5..=5);
+        assert_eq!(run(&["--days=1-8"]).days, 1..=8);
+        assert_eq!(run(&["-p", "2"]).part, Some(Part::Two));
+        assert_eq!(run(&["-d", "3", "-i", "-"]).input, InputSource::Stdin);
+        assert_eq!(
+            run(&["--day=3", "--input", "day3.txt"]).input,
+            InputSource::Path("day3.txt".into())
+        );
+        assert_eq!(
+            run(&["--input-dir", "inputs"]).input,
+            InputSource::Dir("inputs".into())
+        );
+        assert_eq!(run(&["run", "-d", "2"]).days, 2..=2);
+        assert_eq!(parse_args(["--help"]), Ok(Command::Help));
+        assert_eq!(
+            parse_args(["bench", "--days", "1-3", "--iterations", "50"]),
+            Ok(Command::Bench(
+                Options {
+                    days: 1..=3,
+                    ..Options::default()
+                },
+                BenchConfig {
+                    iterations: 50,
+                    ..BenchConfig::default()
+                }
+            ))
+        );
+
+        assert!(parse_args(["--day", "26"]).is_err());
+        assert!(parse_args(["--days", "8-1"]).is_err());
+        assert!(parse_args(["--part", "3"]).is_err());
+        assert!(parse_args(["--day"]).is_err());
+        assert!(parse_args(["--input", "-"]).is_err());
+        assert!(parse_args(["--verbose"]).is_err());
+        assert_eq!(
+            parse_args(["check", "--record", "--answers=answers/2023.txt"]),
+            Ok(Command::Check(
+                Options::default(),
+                CheckConfig {
+                    answers: "answers/2023.txt".into(),
+                    record: true,
+                }
+            ))
+        );
+        assert_eq!(run(&["-f", "csv"]).format, Format::Csv);
+        assert!(parse_args(["--format", "xml"]).is_err());
+        assert!(parse_args(["bench", "--format", "json"]).is_err());
+        assert!(parse_args(["--warmup", "5"]).is_err());
+        assert!(parse_args(["bench", "--record"]).is_err());
+        assert!(parse_args(["bench", "--iterations", "many"]).is_err());
+
+        assert_eq!(
+            parse_args(["new-day", "13", "--fetch", "--cookie=session.txt"]),
+            Ok(Command::NewDay(NewDayConfig {
+                day: 13,
+                fetch: true,
+                cookie: "session.txt".into(),
+            }))
+        );
+        assert_eq!(
+            parse_args(["calibration", "--rewrite", "--lexicon=digits", "-i", "-"]),
+            Ok(Command::Calibration(
+                Options {
+                    days: 1..=1,
+                    input: InputSource::Stdin,
+                    ..Options::default()
+                },
+                CalibrationConfig {
+                    rewrite: true,
+                    lexicon: Preset::Digits,
+                    ignore_case: false,
+                }
+            ))
+        );
+        assert!(parse_args(["calibration", "--day", "2"]).is_err());
+        assert!(parse_args(["calibration", "--lexicon", "french"]).is_err());
+        assert!(parse_args(["--rewrite"]).is_err());
+
+        assert!(parse_args(["new-day"]).is_err());
+        assert!(parse_args(["new-day", "13", "14"]).is_err());
+        assert!(parse_args(["new-day", "13", "--day", "3"]).is_err());
+    }
+}
@@ -0,0 +1,271 @@
+use std::{collections::VecDeque, str::FromStr};
+
+const DIGITS: [(&str, u32); 10] = [
+    ("0", 0),
+    ("1", 1),
+    ("2", 2),
+    ("3", 3),
+    ("4", 4),
+    ("5", 5),
+    ("6", 6),
+    ("7", 7),
+    ("8", 8),
+    ("9", 9),
+];
+
+const ENGLISH_WORDS: [(&str, u32); 9] = [
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+/// The built-in lexicons, for choosing one by name.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    Digits,
+    #[default]
+    English,
+}
+
+impl Preset {
+    pub fn lexicon(self) -> Lexicon {
+        match self {
+            Preset::Digits => Lexicon::digits(),
+            Preset::English => Lexicon::english(),
+        }
+    }
+}
+
+impl FromStr for Preset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "digits" => Ok(Preset::Digits),
+            "english" => Ok(Preset::English),
+            _ => Err(format!("unknown lexicon '{s}', expected digits or english")),
+        }
+    }
+}
+
+/// A recognised token: `line[start..end]` spells `value`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match {
+    pub start: usize,
+    pub end: usize,
+    pub value: u32,
+}
+
+/// The tokens that spell numbers in a calibration document, such as digits and number words.
+/// Matches may overlap, so "eightwo" contains both 8 and 2.
+#[derive(Debug, Clone)]
+pub struct Lexicon {
+    words: Vec<(String, u32)>,
+    case_insensitive: bool,
+    automaton: Automaton,
+}
+
+impl Lexicon {
+    pub fn new<S: Into<String>>(words: impl IntoIterator<Item = (S, u32)>) -> Self {
+        Lexicon::build(
+            words.into_iter().map(|(w, v)| (w.into(), v)).collect(),
+            false,
+        )
+    }
+
+    /// The digits `0` to `9`.
+    pub fn digits() -> Self {
+        Lexicon::new(DIGITS)
+    }
+
+    /// The digits, and the English words for one to nine.
+    pub fn english() -> Self {
+        Lexicon::new(DIGITS.into_iter().chain(ENGLISH_WORDS))
+    }
+
+    pub fn with_words<S: Into<String>>(self, words: impl IntoIterator<Item = (S, u32)>) -> Self {
+        let mut all = self.words;
+        all.extend(words.into_iter().map(|(w, v)| (w.into(), v)));
+        Lexicon::build(all, self.case_insensitive)
+    }
+
+    /// Matches words regardless of ASCII case, so "Eight" and "EIGHT" both spell 8.
+    pub fn case_insensitive(self) -> Self {
+        Lexicon::build(self.words, true)
+    }
+
+    fn build(words: Vec<(String, u32)>, case_insensitive: bool) -> Self {
+        let automaton = Automaton::new(words.iter().map(|(word, _)| {
+            if case_insensitive {
+                word.to_ascii_lowercase()
+            } else {
+                word.clone()
+            }
+        }));
+        Lexicon {
+            words,
+            case_insensitive,
+            automaton,
+        }
+    }
+
+    /// Every token in `line`, including overlapping ones, ordered by where they end.
+    pub fn matches<'a>(&'a self, line: &'a str) -> impl Iterator<Item = Match> + 'a {
+        let mut state = 0;
+        line.bytes().enumerate().flat_map(move |(i, byte)| {
+            let byte = if self.case_insensitive {
+                byte.to_ascii_lowercase()
+            } else {
+                byte
+            };
+            state = self.automaton.next(state, byte);
+            self.automaton.outputs[state].iter().map(move |&word| {
+                let (text, value) = &self.words[word];
+                Match {
+                    start: i + 1 - text.len(),
+                    end: i + 1,
+                    value: *value,
+                }
+            })
+        })
+    }
+
+    /// The token that starts first, preferring the longest of those starting at the same place.
+    pub fn first(&self, line: &str) -> Option<Match> {
+        let mut best: Option<Match> = None;
+        for m in self.matches(line) {
+            // nothing ending from here on can start before the best match
+            if best.is_some_and(|best| m.end > best.start + self.automaton.longest) {
+                break;
+            }
+            if best.is_none_or(|best| {
+                m.start < best.start || (m.start == best.start && m.end > best.end)
+            }) {
+                best = Some(m);
+            }
+        }
+        best
+    }
+
+    /// The token that starts last, preferring the longest of those starting at the same place.
+    pub fn last(&self, line: &str) -> Option<Match> {
+        self.matches(line).max_by_key(|m| (m.start, m.end))
+    }
+}
+
+/// An Aho-Corasick automaton over bytes, with the failure links folded into a full transition table.
+#[derive(Debug, Clone)]
+struct Automaton {
+    transitions: Vec<[usize; 256]>,
+    /// The words ending at each state, including those reached through failure links.
+    outputs: Vec<Vec<usize>>,
+    longest: usize,
+}
+
+impl Automaton {
+    fn new(words: impl Iterator<Item = String>) -> Self {
+        let mut trie = vec![[None; 256]];
+        let mut outputs = vec![vec![]];
+        let mut longest = 0;
+        for (i, word) in words.enumerate() {
+            let mut state = 0;
+            for byte in word.bytes() {
+                state = match trie[state][byte as usize] {
+                    Some(next) => next,
+                    None => {
+                        trie.push([None; 256]);
+                        outputs.push(vec![]);
+                        trie[state][byte as usize] = Some(trie.len() - 1);
+                        trie.len() - 1
+                    }
+                };
+            }
+            outputs[state].push(i);
+            longest = longest.max(word.len());
+        }
+
+        // breadth-first, so that every state's failure target is complete before it is needed
+        let mut transitions = vec![[0; 256]; trie.len()];
+        let mut fail = vec![0; trie.len()];
+        let mut queue = VecDeque::new();
+        for byte in 0..256 {
+            if let Some(next) = trie[0][byte] {
+                transitions[0][byte] = next;
+                queue.push_back(next);
+            }
+        }
+        while let Some(state) = queue.pop_front() {
+            let inherited = outputs[fail[state]].clone();
+            outputs[state].extend(inherited);
+            for byte in 0..256 {
+                match trie[state][byte] {
+                    Some(next) => {
+                        fail[next] = transitions[fail[state]][byte];
+                        transitions[state][byte] = next;
+                        queue.push_back(next);
+                    }
+                    None => transitions[state][byte] = transitions[fail[state]][byte],
+                }
+            }
+        }
+
+        Automaton {
+            transitions,
+            outputs,
+            longest,
+        }
+    }
+
+    fn next(&self, state: usize, byte: u8) -> usize {
+        self.transitions[state][byte as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(lexicon: &Lexicon, line: &str) -> Vec<u32> {
+        lexicon.matches(line).map(|m| m.value).collect()
+    }
+
+    #[test]
+    fn test() {
+        let english = Lexicon::english();
+        assert_eq!(values(&english, "eightwo3"), [8, 2, 3]);
+        assert_eq!(english.first("eightwo").map(|m| m.value), Some(8));
+        assert_eq!(english.last("eightwo").map(|m| m.value), Some(2));
+        assert_eq!(
+            english.last("xoneight"),
+            Some(Match {
+                start: 3,
+                end: 8,
+                value: 8
+            })
+        );
+        assert_eq!(english.first("abc"), None);
+        assert_eq!(values(&english, "EIGHT"), []);
+        assert_eq!(values(&english.case_insensitive(), "EIGHTwo"), [8, 2]);
+
+        let numerals = Lexicon::english().with_words([("seventeen", 17), ("twenty", 20)]);
+        assert_eq!(numerals.first("seventeen").map(|m| m.value), Some(17));
+        assert_eq!(values(&numerals, "twentwenty"), [20]);
+
+        let german = Lexicon::digits().with_words([("eins", 1), ("zwei", 2), ("drei", 3)]);
+        assert_eq!(values(&german, "zweins"), [2, 1]);
+        assert_eq!(values(&german, "dreizwei"), [3, 2]);
+
+        let unicode = Lexicon::new([("fünf", 5), ("ünf", 50)]);
+        assert_eq!(values(&unicode, "xfünf"), [5, 50]);
+        assert_eq!(
+            unicode.first("xfünf").map(|m| (m.start, m.end)),
+            Some((1, 6))
+        );
+    }
+}
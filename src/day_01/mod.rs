@@ -1,89 +1,113 @@
-const DIGIT_WORDS: [&str; 9] = [
-    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
-];
-
-fn solve(input: &str, f: impl Fn(&str) -> (u32, u32)) -> u32 {
-    input
-        .lines()
-        .map(f)
-        .map(|(first, last)| first * 10 + last)
-        .sum()
-}
+use std::sync::LazyLock;
+
+use crate::{
+    parse::ParseError,
+    solution::{Answer, Error, Solution},
+};
+
+pub mod lexicon;
+pub mod spans;
+pub mod stream;
+
+pub use lexicon::{Lexicon, Match, Preset};
+pub use spans::{rewrite, span_report, LineReport, Token};
+pub use stream::{Decoder, NoNumbers, StreamError, Summary};
+
+static DIGITS: LazyLock<Lexicon> = LazyLock::new(Lexicon::digits);
+static ENGLISH: LazyLock<Lexicon> = LazyLock::new(Lexicon::english);
 
-fn part_1_next_digit(line: impl IntoIterator<Item = u8>) -> u8 {
-    line.into_iter()
-        .filter(|b| b.is_ascii_digit())
-        .map(|b| b - b'0')
-        .next()
-        .expect("no digit found in line")
+/// Writes two numbers one after the other, so that 4 and 2 give 42 and 20 and 3 give 203.
+fn combine(first: u32, last: u32) -> u64 {
+    first as u64 * 10u64.pow(last.checked_ilog10().unwrap_or(0) + 1) + last as u64
 }
 
-fn part_1_digits(line: &str) -> (u32, u32) {
-    (
-        part_1_next_digit(line.bytes()) as u32,
-        part_1_next_digit(line.bytes().rev()) as u32,
-    )
+/// The first and last numbers in `line`, combined.
+pub fn calibration_value(lexicon: &Lexicon, line: &str) -> Option<u64> {
+    Some(combine(
+        lexicon.first(line)?.value,
+        lexicon.last(line)?.value,
+    ))
 }
 
-pub fn part_1(input: &str) -> u32 {
-    solve(input, part_1_digits)
+/// Sums the calibration values of every line, failing on the first line without a number.
+pub fn decode(input: &str, lexicon: &Lexicon, expected: &'static str) -> Result<u64, ParseError> {
+    Decoder::new(input.as_bytes(), lexicon, NoNumbers::Error)
+        .expecting(expected)
+        .finish()
+        .map(|summary| summary.total)
+        .map_err(|e| match e {
+            StreamError::Parse(e) => e,
+            StreamError::Io(e) => unreachable!("reading a string can't fail: {e}"),
+        })
 }
 
-fn next_digit_part_2(line: &str, range: impl IntoIterator<Item = usize>) -> Option<u32> {
-    range.into_iter().find_map(|i| {
-        let byte = line.as_bytes()[i];
-        if byte.is_ascii_digit() {
-            Some((byte - b'0') as u32)
-        } else {
-            DIGIT_WORDS
-                .iter()
-                .enumerate()
-                .filter(|(_, w)| line[i..].starts_with(**w))
-                .map(|(j, _)| j as u32 + 1)
-                .next()
-        }
-    })
+pub fn part_1(input: &str) -> Result<u64, ParseError> {
+    decode(input, &DIGITS, "a line containing a digit")
 }
 
-fn part_2_digits(line: &str) -> (u32, u32) {
-    (
-        next_digit_part_2(line, 0..line.len()).expect("no digit found in line"),
-        next_digit_part_2(line, (0..line.len()).rev()).expect("no digit found in line"),
-    )
+pub fn part_2(input: &str) -> Result<u64, ParseError> {
+    decode(input, &ENGLISH, "a line containing a digit or digit word")
 }
 
-pub fn part_2(input: &str) -> u32 {
-    solve(input, part_2_digits)
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input = String;
+
+    fn parse(input: &str) -> Result<String, ParseError> {
+        Ok(input.to_owned())
+    }
+
+    fn part_1(input: &String) -> Result<Answer, Error> {
+        Ok(part_1(input)?.into())
+    }
+
+    fn part_2(input: &String) -> Result<Answer, Error> {
+        Ok(part_2(input)?.into())
+    }
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use indoc::indoc;
 
+    pub(crate) const EXAMPLE_1: &str = indoc! {
+        "
+        1abc2
+        pqr3stu8vwx
+        a1b2c3d4e5f
+        treb7uchet
+        "
+    };
+
+    pub(crate) const EXAMPLE_2: &str = indoc! {
+        "
+        two1nine
+        eightwothree
+        abcone2threexyz
+        xtwone3four
+        4nineeightseven2
+        zoneight234
+        7pqrstsixteen
+        "
+    };
+
     #[test]
     fn test() {
-        let input1 = indoc! {
-            "
-            1abc2
-            pqr3stu8vwx
-            a1b2c3d4e5f
-            treb7uchet
-            "
-        };
-        assert_eq!(part_1(input1), 142);
-
-        let input2 = indoc! {
-            "
-            two1nine
-            eightwothree
-            abcone2threexyz
-            xtwone3four
-            4nineeightseven2
-            zoneight234
-            7pqrstsixteen
-            "
-        };
-        assert_eq!(part_2(input2), 281);
+        assert_eq!(part_1(EXAMPLE_1), Ok(142));
+        assert_eq!(part_2(EXAMPLE_2), Ok(281));
+
+        let err = part_1("1abc2\nabc\n").unwrap_err();
+        assert_eq!(err.location.map(|l| l.line), Some(2));
+        assert!(part_2("1abc2\nabcone\n").is_ok());
+
+        assert_eq!(part_2("eightwo\n"), Ok(82));
+        let numerals = Lexicon::english()
+            .with_words([("twenty", 20)])
+            .case_insensitive();
+        assert_eq!(calibration_value(&numerals, "Twenty1three"), Some(203));
+
+        assert_eq!(part_1(&rewrite(EXAMPLE_2, &ENGLISH)), Ok(281));
     }
 }
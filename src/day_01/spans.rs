@@ -0,0 +1,162 @@
+use std::{fmt, ops::Range};
+
+use super::{combine, Lexicon, Match};
+
+/// A recognised token within a line, and whether it was picked as the line's first or last number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token<'a> {
+    /// Byte offsets within the line.
+    pub span: Range<usize>,
+    pub text: &'a str,
+    pub value: u32,
+    pub first: bool,
+    pub last: bool,
+}
+
+/// How one line of a calibration document was decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineReport<'a> {
+    /// 1-based line number.
+    pub number: usize,
+    pub line: &'a str,
+    pub tokens: Vec<Token<'a>>,
+    /// `None` if the line has no tokens.
+    pub value: Option<u64>,
+}
+
+impl<'a> LineReport<'a> {
+    pub fn new(lexicon: &Lexicon, number: usize, line: &'a str) -> Self {
+        let (first, last) = (lexicon.first(line), lexicon.last(line));
+        let mut tokens = lexicon
+            .matches(line)
+            .map(|m| Token {
+                span: m.start..m.end,
+                text: &line[m.start..m.end],
+                value: m.value,
+                first: first == Some(m),
+                last: last == Some(m),
+            })
+            .collect::<Vec<_>>();
+        tokens.sort_by_key(|token| (token.span.start, token.span.end));
+
+        LineReport {
+            number,
+            line,
+            tokens,
+            value: first
+                .zip(last)
+                .map(|(first, last)| combine(first.value, last.value)),
+        }
+    }
+}
+
+impl fmt::Display for LineReport<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {} -> ", self.number, self.line)?;
+        match self.value {
+            Some(value) => writeln!(f, "{value}")?,
+            None => writeln!(f, "no numbers")?,
+        }
+        for token in &self.tokens {
+            write!(
+                f,
+                "    {:>8} {:?} = {}",
+                format!("{:?}", token.span),
+                token.text,
+                token.value
+            )?;
+            match (token.first, token.last) {
+                (true, true) => writeln!(f, " (first, last)")?,
+                (true, false) => writeln!(f, " (first)")?,
+                (false, true) => writeln!(f, " (last)")?,
+                (false, false) => writeln!(f)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reports how every line of `input` decodes.
+pub fn span_report<'a>(
+    input: &'a str,
+    lexicon: &'a Lexicon,
+) -> impl Iterator<Item = LineReport<'a>> + 'a {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| LineReport::new(lexicon, i + 1, line))
+}
+
+/// Rewrites `input` with every token replaced by its value, keeping everything else as it is.
+/// Tokens that overlap each produce their value, so "eightwo" becomes "82", but a token inside a
+/// longer one, such as "seven" in "seventeen", is only written as part of the longer one.
+pub fn rewrite(input: &str, lexicon: &Lexicon) -> String {
+    let mut out = String::with_capacity(input.len());
+    for line in input.split_inclusive('\n') {
+        let mut matches = lexicon.matches(line).collect::<Vec<_>>();
+        // longest first among tokens starting at the same place, so that contained ones can be dropped
+        matches.sort_by_key(|m| (m.start, std::cmp::Reverse(m.end)));
+
+        let mut kept: Vec<Match> = vec![];
+        for m in matches {
+            if !kept.iter().any(|k| k.start <= m.start && m.end <= k.end) {
+                kept.push(m);
+            }
+        }
+
+        let mut cursor = 0;
+        for m in kept {
+            if m.start > cursor {
+                out.push_str(&line[cursor..m.start]);
+            }
+            out.push_str(&m.value.to_string());
+            cursor = cursor.max(m.end);
+        }
+        out.push_str(&line[cursor..]);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test() {
+        let english = Lexicon::english();
+        let report = LineReport::new(&english, 2, "eightwothree");
+        assert_eq!(report.value, Some(83));
+        assert_eq!(
+            report.tokens[1],
+            Token {
+                span: 4..7,
+                text: "two",
+                value: 2,
+                first: false,
+                last: false
+            }
+        );
+        assert!(report.tokens[0].first && report.tokens[2].last);
+        assert_eq!(
+            report.to_string(),
+            indoc! {r#"
+                line 2: eightwothree -> 83
+                        0..5 "eight" = 8 (first)
+                        4..7 "two" = 2
+                       7..12 "three" = 3 (last)
+            "#}
+        );
+        assert_eq!(
+            LineReport::new(&english, 1, "abc").to_string(),
+            "line 1: abc -> no numbers\n"
+        );
+
+        assert_eq!(
+            rewrite("xeightwoy\nabc\n7pqrstsixteen", &english),
+            "x82y\nabc\n7pqrst6teen"
+        );
+        let numerals = Lexicon::english().with_words([("seventeen", 17)]);
+        assert_eq!(rewrite("seventeenine", &numerals), "179");
+    }
+}
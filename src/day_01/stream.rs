@@ -0,0 +1,177 @@
+use std::{
+    fmt,
+    io::{self, BufRead},
+};
+
+use super::{calibration_value, Lexicon};
+use crate::parse::{Location, ParseError};
+
+/// What to do with a line that has no numbers in it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum NoNumbers {
+    /// Stop decoding with an error.
+    #[default]
+    Error,
+    /// Ignore the line.
+    Skip,
+    /// Ignore the line, but count it in the [`Summary`].
+    Count,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Summary {
+    pub total: u64,
+    pub lines: u64,
+    /// Lines without numbers, when they are being counted.
+    pub without_numbers: u64,
+}
+
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Io(e) => write!(f, "failed to read calibration document: {e}"),
+            StreamError::Parse(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {}
+
+impl From<io::Error> for StreamError {
+    fn from(e: io::Error) -> Self {
+        StreamError::Io(e)
+    }
+}
+
+/// Decodes a calibration document a line at a time, so that it never holds more than one line in memory.
+/// As an iterator it yields the running total after each line.
+pub struct Decoder<'a, R> {
+    reader: R,
+    lexicon: &'a Lexicon,
+    policy: NoNumbers,
+    expected: &'static str,
+    line: String,
+    summary: Summary,
+}
+
+impl<'a, R: BufRead> Decoder<'a, R> {
+    pub fn new(reader: R, lexicon: &'a Lexicon, policy: NoNumbers) -> Self {
+        Decoder {
+            reader,
+            lexicon,
+            policy,
+            expected: "a line containing a number",
+            line: String::new(),
+            summary: Summary::default(),
+        }
+    }
+
+    /// Describes what a line without numbers should have had, for [`NoNumbers::Error`].
+    pub fn expecting(self, expected: &'static str) -> Self {
+        Decoder { expected, ..self }
+    }
+
+    pub fn summary(&self) -> Summary {
+        self.summary
+    }
+
+    /// Decodes the rest of the document.
+    pub fn finish(mut self) -> Result<Summary, StreamError> {
+        for total in &mut self {
+            total?;
+        }
+        Ok(self.summary)
+    }
+
+    fn decode_line(&mut self) -> Result<Option<u64>, StreamError> {
+        self.line.clear();
+        if self.reader.read_line(&mut self.line)? == 0 {
+            return Ok(None);
+        }
+        self.summary.lines += 1;
+
+        let line = self.line.trim_end_matches(['\n', '\r']);
+        match (calibration_value(self.lexicon, line), self.policy) {
+            (Some(value), _) => self.summary.total += value,
+            (None, NoNumbers::Error) => {
+                return Err(StreamError::Parse(ParseError {
+                    location: Some(Location {
+                        line: self.summary.lines as usize,
+                        column: 1,
+                        source_line: line.to_owned(),
+                    }),
+                    ..ParseError::expected(self.expected)
+                }))
+            }
+            (None, NoNumbers::Skip) => {}
+            (None, NoNumbers::Count) => self.summary.without_numbers += 1,
+        }
+        Ok(Some(self.summary.total))
+    }
+}
+
+impl<R: BufRead> Iterator for Decoder<'_, R> {
+    type Item = Result<u64, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.decode_line().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let english = Lexicon::english();
+        let document = "two1nine\r\nabc\n9eight\n";
+
+        let decoder = Decoder::new(document.as_bytes(), &english, NoNumbers::Count);
+        assert_eq!(
+            decoder.collect::<Result<Vec<_>, _>>().unwrap(),
+            [29, 29, 127]
+        );
+        assert_eq!(
+            Decoder::new(document.as_bytes(), &english, NoNumbers::Count)
+                .finish()
+                .unwrap(),
+            Summary {
+                total: 127,
+                lines: 3,
+                without_numbers: 1
+            }
+        );
+        assert_eq!(
+            Decoder::new(document.as_bytes(), &english, NoNumbers::Skip)
+                .finish()
+                .unwrap()
+                .without_numbers,
+            0
+        );
+
+        let mut decoder = Decoder::new(document.as_bytes(), &english, NoNumbers::Error);
+        assert_eq!(decoder.next().unwrap().unwrap(), 29);
+        let Some(Err(StreamError::Parse(e))) = decoder.next() else {
+            panic!("expected an error for the second line");
+        };
+        assert_eq!(e.location.unwrap().line, 2);
+        assert_eq!(decoder.summary().total, 29);
+
+        let numerals = Lexicon::new([("ninety-nine thousand", 99_000)]);
+        let summary = Decoder::new(
+            "ninety-nine thousand\n".repeat(3).as_bytes(),
+            &numerals,
+            NoNumbers::Error,
+        )
+        .finish()
+        .unwrap();
+        assert_eq!(summary.total, 3 * 9_900_099_000);
+    }
+}
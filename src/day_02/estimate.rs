@@ -0,0 +1,264 @@
+use std::ops::RangeInclusive;
+
+use super::{Bag, Colour, Colours, Draw, Game, Input};
+use crate::rng::Rng;
+
+/// The most likely bag for a game, and the log-likelihood of its draws given that bag.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate {
+    pub bag: Bag,
+    pub log_likelihood: f64,
+}
+
+/// Estimates what was in the bag from a game's draws, assuming that each draw takes cubes without
+/// replacement and that they are put back before the next draw, so that every draw is
+/// hypergeometric. Bags are considered up to `max_size` cubes; beforehand every size is equally
+/// likely, and so is every bag of the same size.
+///
+/// Estimates try every bag of up to `max_size` cubes that holds the game's minimal bag. With `c`
+/// colours that is up to `C(max_size + c, c)` bags, roughly `max_size^c / c!`, so `max_size` has to
+/// shrink quickly as the number of colours grows.
+pub struct Estimator {
+    colours: usize,
+    max_size: u32,
+    /// `ln(n!)` for every `n` up to `max_size` plus the number of colours.
+    ln_factorial: Vec<f64>,
+}
+
+impl Estimator {
+    pub fn new(colours: &Colours, max_size: u32) -> Self {
+        let mut ln_factorial = vec![0.0];
+        for n in 1..=max_size + colours.len() as u32 {
+            ln_factorial.push(ln_factorial[n as usize - 1] + (n as f64).ln());
+        }
+        Estimator {
+            colours: colours.len(),
+            max_size,
+            ln_factorial,
+        }
+    }
+
+    fn ln_choose(&self, n: u32, k: u32) -> f64 {
+        self.ln_factorial[n as usize]
+            - self.ln_factorial[k as usize]
+            - self.ln_factorial[(n - k) as usize]
+    }
+
+    /// The log-probability of drawing `draw` from `bag`, or negative infinity if it can't be drawn.
+    pub fn draw_log_likelihood(&self, bag: &Bag, draw: &Draw) -> f64 {
+        let (size, drawn) = (bag.total(), draw.total());
+        if !draw.fits_in(bag) || size > self.max_size as u64 {
+            return f64::NEG_INFINITY;
+        }
+        // both fit in a u32 now, since the draw is no bigger than the bag
+        (0..self.colours)
+            .map(|i| self.ln_choose(bag.count(Colour(i)), draw.count(Colour(i))))
+            .sum::<f64>()
+            - self.ln_choose(size as u32, drawn as u32)
+    }
+
+    pub fn log_likelihood(&self, bag: &Bag, game: &Game) -> f64 {
+        game.draws
+            .iter()
+            .map(|draw| self.draw_log_likelihood(bag, draw))
+            .sum()
+    }
+
+    /// The bag under which the game's draws are most likely, preferring the smallest of equally
+    /// likely bags. `None` if no bag of up to `max_size` cubes could have produced them.
+    pub fn maximum_likelihood(&self, game: &Game) -> Option<Estimate> {
+        let mut best: Option<Estimate> = None;
+        self.for_each_bag(game, |bag, log_likelihood| {
+            let better = best.as_ref().is_none_or(|best| {
+                log_likelihood > best.log_likelihood + 1e-9
+                    || (log_likelihood > best.log_likelihood - 1e-9
+                        && bag.total() < best.bag.total())
+            });
+            if better {
+                best = Some(Estimate {
+                    bag: bag.clone(),
+                    log_likelihood,
+                });
+            }
+        });
+        best
+    }
+
+    /// The probability of each bag size given the game's draws, for the sizes that could have
+    /// produced them, smallest first.
+    pub fn size_posterior(&self, game: &Game) -> Vec<(u32, f64)> {
+        let mut by_size = vec![vec![]; self.max_size as usize + 1];
+        self.for_each_bag(game, |bag, log_likelihood| {
+            by_size[bag.total() as usize].push(log_likelihood)
+        });
+
+        let sizes = by_size
+            .iter()
+            .enumerate()
+            .filter(|(_, lls)| !lls.is_empty())
+            .map(|(size, lls)| {
+                let size = size as u32;
+                let bags = match self.colours {
+                    0 => 0.0,
+                    colours => self.ln_choose(size + colours as u32 - 1, colours as u32 - 1),
+                };
+                (size, log_sum_exp(lls) - bags)
+            })
+            .collect::<Vec<_>>();
+        let total = log_sum_exp(&sizes.iter().map(|&(_, ll)| ll).collect::<Vec<_>>());
+        sizes
+            .into_iter()
+            .map(|(size, ll)| (size, (ll - total).exp()))
+            .collect()
+    }
+
+    /// Calls `f` with every bag of up to `max_size` cubes that could have produced the game's draws.
+    fn for_each_bag(&self, game: &Game, mut f: impl FnMut(&Bag, f64)) {
+        let minimal = game.minimal_bag();
+        let mut bag = Bag {
+            counts: (0..self.colours)
+                .map(|i| minimal.count(Colour(i)))
+                .collect(),
+        };
+        if bag.total() <= self.max_size as u64 {
+            self.extend_bags(game, &mut bag, 0, &mut f);
+        }
+    }
+
+    /// Tries every number of extra cubes of colour `colour` and those after it.
+    fn extend_bags(
+        &self,
+        game: &Game,
+        bag: &mut Bag,
+        colour: usize,
+        f: &mut impl FnMut(&Bag, f64),
+    ) {
+        if colour == self.colours {
+            f(bag, self.log_likelihood(bag, game));
+            return;
+        }
+        let base = bag.counts[colour];
+        for extra in 0..=self.max_size - bag.total() as u32 {
+            bag.counts[colour] = base + extra;
+            self.extend_bags(game, bag, colour + 1, f);
+        }
+        bag.counts[colour] = base;
+    }
+}
+
+fn log_sum_exp(values: &[f64]) -> f64 {
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if max == f64::NEG_INFINITY {
+        return max;
+    }
+    max + values.iter().map(|v| (v - max).exp()).sum::<f64>().ln()
+}
+
+/// Generates game logs from a known bag, drawing the way [`Estimator`] assumes.
+#[derive(Debug, Clone)]
+pub struct Simulation {
+    pub games: u32,
+    pub draws_per_game: RangeInclusive<u64>,
+    /// Draws never take more cubes than the bag holds.
+    pub draw_size: RangeInclusive<u64>,
+}
+
+impl Simulation {
+    pub fn run(&self, colours: &Colours, bag: &Bag, rng: &mut Rng) -> Input {
+        let mut cubes = colours
+            .iter()
+            .flat_map(|colour| std::iter::repeat_n(colour, bag.count(colour) as usize))
+            .collect::<Vec<_>>();
+        let games = (1..=self.games)
+            .map(|id| Game {
+                id,
+                draws: (0..rng.range(self.draws_per_game.clone()))
+                    .map(|_| {
+                        let size = (rng.range(self.draw_size.clone()) as usize).min(cubes.len());
+                        // a partial shuffle puts a uniformly random draw at the front
+                        let mut draw = Draw::default();
+                        for i in 0..size {
+                            let j = i + rng.below((cubes.len() - i) as u64) as usize;
+                            cubes.swap(i, j);
+                            draw.add(cubes[i], 1);
+                        }
+                        draw
+                    })
+                    .collect(),
+            })
+            .collect();
+        Input {
+            colours: colours.clone(),
+            games,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day_02::input_generator;
+
+    #[test]
+    fn test() {
+        let input = input_generator("Game 1: 2 red, 1 blue; 1 green\n").unwrap();
+        let estimator = Estimator::new(&input.colours, 10);
+        let game = &input.games[0];
+
+        // three of the four cubes, then one of the four
+        let bag = input.draw([("red", 2), ("blue", 1), ("green", 1)]);
+        assert!((estimator.log_likelihood(&bag, game) - (1.0f64 / 16.0).ln()).abs() < 1e-9);
+        assert_eq!(
+            estimator.log_likelihood(&input.draw([("red", 1)]), game),
+            f64::NEG_INFINITY
+        );
+        assert_eq!(estimator.maximum_likelihood(game).unwrap().bag, bag);
+
+        let posterior = estimator.size_posterior(game);
+        assert_eq!(posterior.first().unwrap().0, 4);
+        assert_eq!(posterior.last().unwrap().0, 10);
+        assert!((posterior.iter().map(|(_, p)| p).sum::<f64>() - 1.0).abs() < 1e-9);
+
+        assert!(Estimator::new(&input.colours, 3)
+            .maximum_likelihood(game)
+            .is_none());
+    }
+
+    #[test]
+    fn test_simulation() {
+        let colours = input_generator("Game 1: 1 red, 1 green, 1 blue\n")
+            .unwrap()
+            .colours;
+        let mut bag = Bag::default();
+        for (name, count) in [("red", 5), ("green", 3), ("blue", 4)] {
+            bag.add(colours.get(name).unwrap(), count);
+        }
+        let simulation = Simulation {
+            games: 3,
+            draws_per_game: 100..=120,
+            draw_size: 6..=20,
+        };
+        let log = simulation.run(&colours, &bag, &mut Rng::new(2023));
+
+        // the log reads back as it was written
+        let parsed = input_generator(&log.to_string()).unwrap();
+        assert_eq!(parsed.games.len(), 3);
+        assert!(parsed
+            .games
+            .iter()
+            .zip(&log.games)
+            .all(|(a, b)| a.draws == b.draws));
+
+        let estimator = Estimator::new(&colours, 16);
+        for game in &log.games {
+            assert!(game.draws.iter().all(|draw| draw.fits_in(&bag)));
+            assert_eq!(estimator.maximum_likelihood(game).unwrap().bag, bag);
+            let (size, _) = estimator
+                .size_posterior(game)
+                .into_iter()
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .unwrap();
+            assert_eq!(size, 12);
+        }
+    }
+}
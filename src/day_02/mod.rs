@@ -1,136 +1,331 @@
+use std::fmt;
+
 use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::{char, line_ending, space1, u32, u8},
+    character::complete::{alpha1, char, space1, u32},
+    combinator::{cut, verify},
+    error::context,
     multi::separated_list0,
-    sequence::{delimited, tuple},
-    Parser,
+    sequence::{delimited, preceded, separated_pair, tuple},
+};
+use rustc_hash::FxHashMap;
+
+use crate::{
+    parse::{parse_lines, tag, ParseError},
+    solution::{Answer, Error, SolveError, Solution},
 };
 
+pub mod estimate;
+pub mod query;
+
+/// An interned colour, indexing into [`Colours`] and into the counts of a [`Draw`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Colour(usize);
+
+/// Every colour seen in the input, in the order they first appeared.
+#[derive(Debug, Default, Clone)]
+pub struct Colours {
+    names: Vec<String>,
+    by_name: FxHashMap<String, Colour>,
+}
+
+impl Colours {
+    pub fn intern(&mut self, name: &str) -> Colour {
+        if let Some(&colour) = self.by_name.get(name) {
+            return colour;
+        }
+        let colour = Colour(self.names.len());
+        self.names.push(name.to_owned());
+        self.by_name.insert(name.to_owned(), colour);
+        colour
+    }
+
+    pub fn get(&self, name: &str) -> Option<Colour> {
+        self.by_name.get(name).copied()
+    }
+
+    pub fn name(&self, colour: Colour) -> &str {
+        &self.names[colour.0]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Colour> {
+        (0..self.names.len()).map(Colour)
+    }
+}
+
 pub struct Input {
-    games: Vec<Game>,
+    pub colours: Colours,
+    pub games: Vec<Game>,
 }
 
 pub struct Game {
-    id: u32,
-    draws: Vec<Draw>,
+    pub id: u32,
+    pub draws: Vec<Draw>,
 }
 
+/// How many cubes of each colour; colours it doesn't mention have none.
+#[derive(Debug, Default, Clone)]
 pub struct Draw {
-    red: u8,
-    green: u8,
-    blue: u8,
+    counts: Vec<u32>,
+}
+
+/// The cubes a game might have been played with, counted the same way as a draw.
+pub type Bag = Draw;
+
+impl PartialEq for Draw {
+    fn eq(&self, other: &Draw) -> bool {
+        self.fits_in(other) && other.fits_in(self)
+    }
 }
 
-pub type IResult<'a, T> = nom::IResult<&'a [u8], T>;
+impl Eq for Draw {}
 
-pub fn input_generator(input: &str) -> Input {
-    enum Cols {
-        Red,
-        Green,
-        Blue,
+impl Draw {
+    pub fn count(&self, colour: Colour) -> u32 {
+        self.counts.get(colour.0).copied().unwrap_or(0)
     }
 
-    let games: IResult<Vec<Game>> = separated_list0(
-        line_ending,
+    /// Panics if the colour's count no longer fits in a `u32`.
+    pub fn add(&mut self, colour: Colour, count: u32) {
+        if self.counts.len() <= colour.0 {
+            self.counts.resize(colour.0 + 1, 0);
+        }
+        self.counts[colour.0] += count;
+    }
+
+    /// The number of cubes of all colours.
+    pub fn total(&self) -> u64 {
+        self.counts.iter().map(|&count| count as u64).sum()
+    }
+
+    /// Whether every colour's count is within `other`'s.
+    pub fn fits_in(&self, other: &Draw) -> bool {
+        self.counts
+            .iter()
+            .enumerate()
+            .all(|(i, &count)| count <= other.count(Colour(i)))
+    }
+
+    /// The larger count of each colour.
+    pub fn max(&self, other: &Draw) -> Draw {
+        let len = self.counts.len().max(other.counts.len());
+        Draw {
+            counts: (0..len)
+                .map(|i| self.count(Colour(i)).max(other.count(Colour(i))))
+                .collect(),
+        }
+    }
+}
+
+impl Input {
+    /// A draw from named counts, ignoring colours that never appear in the input.
+    pub fn draw<'a>(&self, counts: impl IntoIterator<Item = (&'a str, u32)>) -> Draw {
+        let mut draw = Draw::default();
+        for (name, count) in counts {
+            if let Some(colour) = self.colours.get(name) {
+                draw.add(colour, count);
+            }
+        }
+        draw
+    }
+}
+
+/// Writes the games back out in the puzzle's format.
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for game in &self.games {
+            write!(f, "Game {}:", game.id)?;
+            for (i, draw) in game.draws.iter().enumerate() {
+                let cubes = self
+                    .colours
+                    .iter()
+                    .filter(|&colour| draw.count(colour) > 0)
+                    .map(|colour| format!(" {} {}", draw.count(colour), self.colours.name(colour)))
+                    .collect::<Vec<_>>();
+                write!(f, "{}{}", if i > 0 { ";" } else { "" }, cubes.join(","))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let games = parse_lines(
+        input,
+        input,
         tuple((
             delimited(tag("Game "), u32, char(':')),
             separated_list0(
                 char(';'),
-                separated_list0(
-                    char(','),
-                    tuple((
-                        space1,
-                        u8,
-                        space1,
-                        alt((
-                            tag("red").map(|_| Cols::Red),
-                            tag("green").map(|_| Cols::Green),
-                            tag("blue").map(|_| Cols::Blue),
-                        )),
-                    )),
-                )
-                .map(|draw| {
-                    draw.into_iter().fold(
-                        Draw {
-                            red: 0,
-                            green: 0,
-                            blue: 0,
-                        },
-                        |mut draw, (_, count, _, col)| {
-                            match col {
-                                Cols::Red => draw.red += count,
-                                Cols::Green => draw.green += count,
-                                Cols::Blue => draw.blue += count,
-                            };
-                            draw
+                cut(context(
+                    "a draw with fewer than 2^32 cubes of each colour",
+                    verify(
+                        separated_list0(
+                            char(','),
+                            separated_pair(
+                                preceded(space1, u32),
+                                space1,
+                                // a count must be followed by a colour, so don't backtrack past it
+                                cut(context("a colour", alpha1)),
+                            ),
+                        ),
+                        |cubes: &Vec<(u32, &str)>| {
+                            let mut totals = FxHashMap::<&str, u32>::default();
+                            cubes.iter().all(|&(count, name)| {
+                                let total = totals.entry(name).or_default();
+                                total.checked_add(count).map(|sum| *total = sum).is_some()
+                            })
                         },
-                    )
-                }),
+                    ),
+                )),
             ),
-        ))
-        .map(|(id, draws)| Game { id, draws }),
-    )(input.as_bytes());
+        )),
+    )?;
 
-    let (_, games) = games.expect("failed to parse");
+    let mut colours = Colours::default();
+    let games = games
+        .into_iter()
+        .map(|(id, draws)| Game {
+            id,
+            draws: draws
+                .into_iter()
+                .map(|cubes| {
+                    let mut draw = Draw::default();
+                    for (count, name) in cubes {
+                        draw.add(colours.intern(name), count);
+                    }
+                    draw
+                })
+                .collect(),
+        })
+        .collect();
 
-    Input { games }
+    Ok(Input { colours, games })
+}
+
+/// The product of the fewest cubes of every colour in the input that `game` could have been played
+/// with, which is zero if it never draws one of them, or `None` if it doesn't fit in a `u64`.
+pub fn power(colours: &Colours, game: &Game) -> Option<u64> {
+    let fewest = game.minimal_bag();
+    colours.iter().try_fold(1u64, |power, colour| {
+        power.checked_mul(fewest.count(colour) as u64)
+    })
 }
 
 pub fn part_1(input: &Input) -> u32 {
-    const RED: u8 = 12;
-    const GREEN: u8 = 13;
-    const BLUE: u8 = 14;
+    let bag = input.draw([("red", 12), ("green", 13), ("blue", 14)]);
+    input.feasible(&bag).map(|game| game.id).sum()
+}
 
+pub fn part_2(input: &Input) -> Result<u64, SolveError> {
     input
         .games
         .iter()
-        .filter(|game| {
-            game.draws
-                .iter()
-                .all(|draw| draw.red <= RED && draw.blue <= BLUE && draw.green <= GREEN)
+        .try_fold(0u64, |sum, game| {
+            power(&input.colours, game)?.checked_add(sum)
         })
-        .map(|g| g.id)
-        .sum()
+        .ok_or(SolveError::Overflow("the sum of the powers".into()))
 }
 
-pub fn part_2(input: &Input) -> u32 {
-    input
-        .games
-        .iter()
-        .map(|game| {
-            let (red, green, blue) =
-                game.draws
-                    .iter()
-                    .fold((0, 0, 0), |(mut r, mut g, mut b), draw| {
-                        r = r.max(draw.red);
-                        g = g.max(draw.green);
-                        b = b.max(draw.blue);
-                        (r, g, b)
-                    });
-
-            (red as u32) * (green as u32) * (blue as u32)
-        })
-        .sum()
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input = Input;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part_1(input: &Input) -> Result<Answer, Error> {
+        Ok(part_1(input).into())
+    }
+
+    fn part_2(input: &Input) -> Result<Answer, Error> {
+        Ok(part_2(input)?.into())
+    }
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use indoc::indoc;
 
+    pub(crate) const EXAMPLE: &str = indoc! {
+        "
+        Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+        Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+        Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+        Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+        Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+        "
+    };
+
     #[test]
     fn test() {
-        let input = input_generator(indoc! {
-            "
-            Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-            Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-            Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-            Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-            Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
-            "
-        });
+        let input = input_generator(EXAMPLE).unwrap();
         assert_eq!(part_1(&input), 8);
-        assert_eq!(part_2(&input), 2286);
+        assert_eq!(part_2(&input), Ok(2286));
+
+        let input = input_generator(indoc! {"
+            Game 1: 3 blue, 300 purple; 2 red, 1 blue, 4 blue
+            Game 2: 5000000000 red
+            Game 3: 1 purple, 2 red, 1 blue
+        "});
+        assert!(input.is_err());
+        let input = input_generator(indoc! {"
+            Game 1: 3 blue, 300 purple; 2 red, 1 blue, 4 blue
+            Game 2: 4000000000 red
+            Game 3: 1 purple, 2 red, 1 blue
+        "})
+        .unwrap();
+        assert_eq!(input.colours.len(), 3);
+        let purple = input.colours.get("purple").unwrap();
+        assert_eq!(input.colours.name(purple), "purple");
+        assert_eq!(input.games[0].draws[1].count(purple), 0);
+        assert_eq!(
+            input.games[0].draws[1].count(input.colours.get("blue").unwrap()),
+            5
+        );
+        assert_eq!(part_1(&input), 0);
+        assert_eq!(
+            input
+                .feasible(&input.draw([("red", 2), ("blue", 5), ("purple", 300)]))
+                .map(|game| game.id)
+                .collect::<Vec<_>>(),
+            [1, 3]
+        );
+        assert_eq!(part_2(&input), Ok(2 * 5 * 300 + 2));
+
+        let err = input_generator("Game 1: 4000000000 red, 4000000000 red\n")
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.expected,
+            "a draw with fewer than 2^32 cubes of each colour"
+        );
+        assert_eq!(err.location.unwrap().column, 8);
+        let input = input_generator(indoc! {"
+            Game 1: 4000000000 red, 4000000000 green, 4000000000 blue
+            Game 2: 1 red, 1 green, 1 blue
+        "})
+        .unwrap();
+        assert!(part_2(&input).is_err());
+        assert_eq!(input.games[0].draws[0].total(), 12_000_000_000);
+        let estimator = estimate::Estimator::new(&input.colours, 10);
+        assert_eq!(estimator.maximum_likelihood(&input.games[0]), None);
+
+        let err = input_generator("Game 1: 3 blue\nGame 2: 4 42\n")
+            .err()
+            .unwrap();
+        assert_eq!(err.expected, "a colour");
+        assert_eq!(err.location.unwrap().column, 11);
     }
 }
@@ -0,0 +1,197 @@
+use super::{Bag, Colour, Draw, Game, Input};
+
+impl Game {
+    /// The fewest cubes of each colour this game could have been played with.
+    pub fn minimal_bag(&self) -> Bag {
+        self.draws
+            .iter()
+            .fold(Bag::default(), |fewest, draw| fewest.max(draw))
+    }
+
+    pub fn is_feasible(&self, bag: &Bag) -> bool {
+        self.draws.iter().all(|draw| draw.fits_in(bag))
+    }
+
+    /// The first draw that needs more cubes of some colour than `bag` has, with its index.
+    pub fn ruled_out_by(&self, bag: &Bag) -> Option<(usize, &Draw)> {
+        self.draws
+            .iter()
+            .enumerate()
+            .find(|(_, draw)| !draw.fits_in(bag))
+    }
+}
+
+impl Input {
+    /// The games that could have been played with `bag`.
+    pub fn feasible<'a>(&'a self, bag: &'a Bag) -> impl Iterator<Item = &'a Game> + 'a {
+        self.games.iter().filter(|game| game.is_feasible(bag))
+    }
+
+    /// The smallest bags that make at least `k` games feasible: every such bag holds at least as
+    /// many cubes of each colour as one of these, and none of these holds at least as many as
+    /// another. Sorted by their counts, colour by colour.
+    pub fn frontier(&self, k: usize) -> Vec<Bag> {
+        if k > self.games.len() {
+            return vec![];
+        }
+        let minimal = self.games.iter().map(Game::minimal_bag).collect::<Vec<_>>();
+        // a smallest bag is the largest of some games' minimal bags, so each of its counts is one of theirs
+        let candidates = self
+            .colours
+            .iter()
+            .map(|colour| {
+                let mut counts = minimal
+                    .iter()
+                    .map(|bag| bag.count(colour))
+                    .collect::<Vec<_>>();
+                counts.push(0);
+                counts.sort_unstable();
+                counts.dedup();
+                counts
+            })
+            .collect::<Vec<_>>();
+
+        let mut bags = vec![];
+        let mut counts = Vec::with_capacity(candidates.len());
+        smallest_bags(&minimal, &candidates, k, &mut counts, &mut bags);
+        bags.sort_by(|a: &Bag, b| a.counts.cmp(&b.counts));
+        bags.dedup();
+
+        let mut frontier: Vec<Bag> = vec![];
+        for bag in bags {
+            // anything it dominates sorts after it, so checking the kept bags is enough
+            if !frontier.iter().any(|kept| kept.fits_in(&bag)) {
+                frontier.push(bag);
+            }
+        }
+        frontier
+    }
+}
+
+/// Fixes the colours before the last to each combination of candidate counts, and gives the last
+/// colour the fewest cubes that still admit `k` games.
+fn smallest_bags(
+    minimal: &[Bag],
+    candidates: &[Vec<u32>],
+    k: usize,
+    counts: &mut Vec<u32>,
+    bags: &mut Vec<Bag>,
+) {
+    let colour = counts.len();
+    if colour + 1 >= candidates.len() {
+        let mut last = minimal
+            .iter()
+            .filter(|bag| fits_prefix(bag, counts))
+            .map(|bag| bag.count(Colour(colour)))
+            .collect::<Vec<_>>();
+        if last.len() < k {
+            return;
+        }
+        let mut counts = counts.clone();
+        if colour < candidates.len() {
+            last.sort_unstable();
+            counts.push(k.checked_sub(1).map_or(0, |i| last[i]));
+        }
+        bags.push(Bag { counts });
+        return;
+    }
+
+    for &count in &candidates[colour] {
+        counts.push(count);
+        if minimal
+            .iter()
+            .filter(|bag| fits_prefix(bag, counts))
+            .count()
+            >= k
+        {
+            smallest_bags(minimal, candidates, k, counts, bags);
+        }
+        counts.pop();
+    }
+}
+
+fn fits_prefix(bag: &Bag, counts: &[u32]) -> bool {
+    counts
+        .iter()
+        .enumerate()
+        .all(|(i, &count)| bag.count(Colour(i)) <= count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day_02::input_generator;
+    use indoc::indoc;
+
+    const EXAMPLE: &str = indoc! {
+        "
+        Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+        Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+        Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+        Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+        Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+        "
+    };
+
+    /// The largest minimal bag of every `k` games, keeping those that no other is within.
+    fn naive_frontier(input: &Input, k: usize) -> Vec<Bag> {
+        let minimal = input
+            .games
+            .iter()
+            .map(Game::minimal_bag)
+            .collect::<Vec<_>>();
+        let bags = (0u32..1 << minimal.len())
+            .filter(|subset| subset.count_ones() as usize == k)
+            .map(|subset| {
+                (0..minimal.len())
+                    .filter(|i| subset & (1 << i) != 0)
+                    .fold(Bag::default(), |bag, i| bag.max(&minimal[i]))
+            })
+            .collect::<Vec<_>>();
+        bags.iter()
+            .filter(|bag| !bags.iter().any(|other| other.fits_in(bag) && other != *bag))
+            .fold(vec![], |mut frontier, bag| {
+                if !frontier.contains(bag) {
+                    frontier.push(bag.clone());
+                }
+                frontier
+            })
+    }
+
+    #[test]
+    fn test() {
+        let input = input_generator(EXAMPLE).unwrap();
+        let bag = input.draw([("red", 12), ("green", 13), ("blue", 14)]);
+
+        assert_eq!(
+            input.games[0].minimal_bag(),
+            input.draw([("red", 4), ("green", 2), ("blue", 6)])
+        );
+        assert_eq!(
+            input.feasible(&bag).map(|game| game.id).collect::<Vec<_>>(),
+            [1, 2, 5]
+        );
+        assert_eq!(input.games[0].ruled_out_by(&bag), None);
+        assert_eq!(input.games[3].ruled_out_by(&bag).map(|(i, _)| i), Some(2));
+
+        assert_eq!(
+            input.frontier(1),
+            [
+                input.draw([("red", 6), ("green", 3), ("blue", 2)]),
+                input.draw([("red", 1), ("green", 3), ("blue", 4)]),
+                input.draw([("red", 4), ("green", 2), ("blue", 6)]),
+            ]
+        );
+        assert_eq!(input.frontier(0), [Bag::default()]);
+        assert_eq!(
+            input.frontier(5),
+            [input.draw([("red", 20), ("green", 13), ("blue", 15)])]
+        );
+        assert_eq!(input.frontier(6), []);
+        for k in 0..=5 {
+            let mut naive = naive_frontier(&input, k);
+            naive.sort_by(|a, b| a.counts.cmp(&b.counts));
+            assert_eq!(input.frontier(k), naive, "k = {k}");
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use super::Schematic;
+use crate::solution::SolveError;
+
+/// How many parts a symbol needs to be a gear.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arity {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+/// How a gear's part numbers combine into its ratio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Combine {
+    Product,
+    Sum,
+    Max,
+}
+
+impl Combine {
+    /// The combined value, or `None` if it doesn't fit in a `u64`.
+    pub fn apply(self, mut values: impl Iterator<Item = u64>) -> Option<u64> {
+        match self {
+            Combine::Product => values.try_fold(1u64, u64::checked_mul),
+            Combine::Sum => values.try_fold(0u64, u64::checked_add),
+            Combine::Max => Some(values.max().unwrap_or(0)),
+        }
+    }
+}
+
+/// Which symbols are gears and how their ratios are worked out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GearSpec {
+    pub symbols: Vec<char>,
+    pub arity: Arity,
+    pub combine: Combine,
+}
+
+impl Default for GearSpec {
+    /// A `*` next to exactly two parts, with their product as its ratio.
+    fn default() -> Self {
+        GearSpec {
+            symbols: vec!['*'],
+            arity: Arity::Exactly(2),
+            combine: Combine::Product,
+        }
+    }
+}
+
+/// A gear symbol and its parts, by index into the schematic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gear {
+    pub symbol: usize,
+    pub parts: Vec<usize>,
+    pub ratio: u64,
+}
+
+/// A gear symbol next to more parts than an exact arity allows, so that it's unclear which of them
+/// it should drive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ambiguous {
+    pub symbol: usize,
+    pub parts: Vec<usize>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GearReport {
+    pub gears: Vec<Gear>,
+    pub ambiguous: Vec<Ambiguous>,
+}
+
+impl GearReport {
+    /// The sum of the gears' ratios, or `None` if it doesn't fit in a `u64`.
+    pub fn total_ratio(&self) -> Option<u64> {
+        self.gears
+            .iter()
+            .try_fold(0u64, |total, gear| total.checked_add(gear.ratio))
+    }
+}
+
+impl Schematic {
+    /// The gears according to `spec`, in reading order, and the symbols that have too many parts.
+    /// Fails if a gear's ratio doesn't fit in a `u64`.
+    pub fn gears(&self, spec: &GearSpec) -> Result<GearReport, SolveError> {
+        let mut report = GearReport::default();
+        for (symbol, cell) in self.symbols().iter().enumerate() {
+            if !spec.symbols.contains(&cell.symbol) {
+                continue;
+            }
+            let parts = self.adjacent_number_indices(symbol).to_vec();
+            match spec.arity {
+                Arity::Exactly(n) if parts.len() > n => {
+                    report.ambiguous.push(Ambiguous { symbol, parts });
+                    continue;
+                }
+                Arity::Exactly(n) | Arity::AtLeast(n) if parts.len() < n => continue,
+                _ => {}
+            }
+            let ratio = spec
+                .combine
+                .apply(parts.iter().map(|&i| self.numbers()[i].value))
+                .ok_or_else(|| {
+                    let (row, col) = self.symbols()[symbol].pos;
+                    SolveError::Overflow(
+                        format!(
+                            "the ratio of the '{}' at line {}, column {}",
+                            cell.symbol,
+                            row + 1,
+                            col + 1
+                        )
+                        .into(),
+                    )
+                })?;
+            report.gears.push(Gear {
+                symbol,
+                parts,
+                ratio,
+            });
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test() {
+        let schematic = Schematic::parse(indoc! {"
+            2.3...7.
+            .*...#..
+            4.......
+            ..10+5..
+        "})
+        .unwrap();
+
+        let report = schematic.gears(&GearSpec::default()).unwrap();
+        assert_eq!(report.gears, []);
+        assert_eq!(
+            report.ambiguous,
+            [Ambiguous {
+                symbol: 0,
+                parts: vec![0, 1, 3]
+            }]
+        );
+
+        let spec = GearSpec {
+            symbols: vec!['*', '+', '#'],
+            arity: Arity::AtLeast(2),
+            combine: Combine::Sum,
+        };
+        let report = schematic.gears(&spec).unwrap();
+        assert_eq!(
+            report.gears.iter().map(|g| g.ratio).collect::<Vec<_>>(),
+            [9, 15]
+        );
+        assert!(report.ambiguous.is_empty());
+
+        let spec = GearSpec {
+            arity: Arity::Exactly(1),
+            combine: Combine::Max,
+            ..spec
+        };
+        let report = schematic.gears(&spec).unwrap();
+        assert_eq!(report.total_ratio(), Some(7));
+        assert_eq!(report.ambiguous.len(), 2);
+    }
+}
@@ -1,149 +1,145 @@
-use std::str::Lines;
+use crate::{
+    parse::ParseError,
+    solution::{Answer, Error, SolveError, Solution},
+};
 
-struct LineIterator<'a> {
-    lines: Lines<'a>,
-    head: Option<&'a str>,
-    mid: Option<&'a str>,
-    tail: Option<&'a str>,
-}
-
-impl<'a> LineIterator<'a> {
-    fn new(input: &'a str) -> Self {
-        LineIterator {
-            lines: input.lines(),
-            head: None,
-            mid: None,
-            tail: None,
-        }
-    }
-}
-
-impl<'a> Iterator for LineIterator<'a> {
-    type Item = (Option<&'a str>, Option<&'a str>, Option<&'a str>);
+pub mod gears;
+pub mod schematic;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.mid.is_none() {
-            self.head = None;
-            self.mid = self.lines.next();
-            self.tail = self.lines.next();
-        } else {
-            self.head = self.mid;
-            self.mid = self.tail;
-            self.tail = self.lines.next();
+pub use gears::{Ambiguous, Arity, Combine, Gear, GearReport, GearSpec};
+pub use schematic::{Component, Number, Schematic, Shape, Span, Symbol};
 
-            self.head?;
-        }
+pub type Input = Schematic;
 
-        Some((self.head, self.mid, self.tail))
-    }
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    Schematic::parse(input)
 }
 
-fn parse_num_at(line: &str, pos: usize) -> u32 {
-    let pos = line
-        .bytes()
-        .enumerate()
-        .rev()
-        .skip(line.len() - pos - 1)
-        .take_while(|(_, b)| b.is_ascii_digit())
-        .map(|(i, _)| i)
-        .last()
-        .unwrap();
-
-    line.bytes()
-        .skip(pos)
-        .take_while(|b| b.is_ascii_digit())
-        .fold(0, |sum, digit| sum * 10 + (digit - b'0') as u32)
+pub fn part_1(input: &Input) -> Result<u64, SolveError> {
+    input
+        .part_numbers()
+        .try_fold(0u64, |sum, number| sum.checked_add(number.value))
+        .ok_or(SolveError::Overflow("the sum of the part numbers".into()))
 }
 
-fn nums_at_nbours(line: &str, i: usize) -> [Option<u32>; 3] {
-    let mut out = [None; 3];
-    if matches!(line.as_bytes().get(i), Some(b) if b.is_ascii_digit()) {
-        out[1] = Some(parse_num_at(line, i));
-        // if the middle one has a number, then it must be the only one (since it would be neighbouring the other two positions)
-        // exit now to avoid counting a number twice
-        return out;
-    }
+pub fn part_2(input: &Input) -> Result<u64, SolveError> {
+    input
+        .gears(&GearSpec::default())?
+        .total_ratio()
+        .ok_or(SolveError::Overflow("the sum of the gear ratios".into()))
+}
 
-    if i > 0 && matches!(line.as_bytes().get(i - 1), Some(b) if b.is_ascii_digit()) {
-        out[0] = Some(parse_num_at(line, i - 1));
-    }
-    if matches!(line.as_bytes().get(i + 1), Some(b) if b.is_ascii_digit()) {
-        out[2] = Some(parse_num_at(line, i + 1));
-    }
+pub struct Day03;
 
-    out
-}
+impl Solution for Day03 {
+    type Input = Input;
 
-pub fn part_1(input: &str) -> u32 {
-    let mut line_iter = LineIterator::new(input);
-    let mut sum = 0;
-
-    while let Some((head, Some(mid_line), tail)) = line_iter.next() {
-        for (i, _) in mid_line
-            .bytes()
-            .enumerate()
-            .filter(|(_, b)| !b.is_ascii_digit() && b != &b'.')
-        {
-            let (head_line, tail_line) = (head.unwrap_or(""), tail.unwrap_or(""));
-            sum += nums_at_nbours(head_line, i).iter().flatten().sum::<u32>();
-            sum += nums_at_nbours(mid_line, i).iter().flatten().sum::<u32>();
-            sum += nums_at_nbours(tail_line, i).iter().flatten().sum::<u32>();
-        }
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input_generator(input)
     }
 
-    sum
-}
-
-pub fn part_2(input: &str) -> u32 {
-    let mut line_iter = LineIterator::new(input);
-    let mut sum = 0;
-
-    while let Some((head, Some(mid_line), tail)) = line_iter.next() {
-        for (i, _) in mid_line.bytes().enumerate().filter(|(_, b)| b == &b'*') {
-            let mut first = None;
-            let (head_line, tail_line) = (head.unwrap_or(""), tail.unwrap_or(""));
-            let items = nums_at_nbours(mid_line, i)
-                .into_iter()
-                .chain(nums_at_nbours(head_line, i))
-                .chain(nums_at_nbours(tail_line, i));
-
-            for item in items.flatten() {
-                match first {
-                    Some(first) => {
-                        sum += item * first;
-                        break;
-                    }
-                    None => first = Some(item),
-                }
-            }
-        }
+    fn part_1(input: &Input) -> Result<Answer, Error> {
+        Ok(part_1(input)?.into())
     }
 
-    sum
+    fn part_2(input: &Input) -> Result<Answer, Error> {
+        Ok(part_2(input)?.into())
+    }
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use indoc::indoc;
 
+    pub(crate) const EXAMPLE: &str = indoc! {
+        "
+        467..114..
+        ...*......
+        ..35..633.
+        ......#...
+        617*......
+        .....+.58.
+        ..592.....
+        ......755.
+        ...$.*....
+        .664.598..
+        "
+    };
+
     #[test]
     fn test() {
-        let input = indoc! {
-            "
-            467..114..
-            ...*......
-            ..35..633.
-            ......#...
-            617*......
-            .....+.58.
-            ..592.....
-            ......755.
-            ...$.*....
-            .664.598..
-            "
-        };
-        assert_eq!(part_1(input), 4361);
-        assert_eq!(part_2(input), 467835);
+        let input = input_generator(EXAMPLE).unwrap();
+        assert_eq!(part_1(&input), Ok(4361));
+        assert_eq!(part_2(&input), Ok(467835));
+
+        assert_eq!(input.numbers().len(), 10);
+        assert_eq!(
+            input.numbers()[2],
+            Number {
+                value: 35,
+                span: Span {
+                    row: 2,
+                    start: 2,
+                    end: 4
+                }
+            }
+        );
+        assert_eq!(
+            input.symbols()[0],
+            Symbol {
+                symbol: '*',
+                pos: (1, 3)
+            }
+        );
+        assert_eq!(
+            input
+                .adjacent_numbers(0)
+                .map(|number| number.value)
+                .collect::<Vec<_>>(),
+            [467, 35]
+        );
+        assert_eq!(
+            input.adjacent_symbols(6).collect::<Vec<_>>(),
+            [&input.symbols()[3]]
+        );
+        assert_eq!(
+            input
+                .orphans()
+                .map(|number| number.value)
+                .collect::<Vec<_>>(),
+            [114, 58]
+        );
+        let components = input.components();
+        assert_eq!(components.len(), 8);
+        assert_eq!(
+            components[0],
+            Component {
+                numbers: vec![0, 2],
+                symbols: vec![0]
+            }
+        );
+        assert!(components
+            .iter()
+            .any(|c| c.numbers == [1] && c.symbols.is_empty()));
+
+        let err = Schematic::parse(
+            "1.......................\n.*......................\n.99999999999999999999999\n",
+        )
+        .err()
+        .unwrap();
+        assert_eq!(err.expected, "a number that fits in 64 bits");
+        assert_eq!(err.location.unwrap().line, 3);
+
+        let input = input_generator("99999999999.\n...*........\n.99999999999\n").unwrap();
+        assert_eq!(
+            part_2(&input),
+            Err(SolveError::Overflow(
+                "the ratio of the '*' at line 2, column 4".into()
+            ))
+        );
+        let input = input_generator("18446744073709551615*1\n").unwrap();
+        assert!(part_1(&input).is_err());
+        assert_eq!(part_2(&input), Ok(18446744073709551615));
     }
 }
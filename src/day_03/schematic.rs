@@ -0,0 +1,406 @@
+use std::collections::VecDeque;
+
+use rustc_hash::FxHashMap;
+
+use crate::{
+    grid::{Grid, Pos, DIRECTIONS_8},
+    parse::{Location, ParseError},
+};
+
+/// Where a number is written: columns `start..end` of `row`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub row: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn contains(&self, (row, col): Pos) -> bool {
+        row == self.row && (self.start..self.end).contains(&col)
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let row = self.row;
+        (self.start..self.end).map(move |col| (row, col))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Number {
+    pub value: u64,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Symbol {
+    pub symbol: char,
+    pub pos: Pos,
+}
+
+/// Numbers and symbols that are connected through adjacency, by index.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Component {
+    pub numbers: Vec<usize>,
+    pub symbols: Vec<usize>,
+}
+
+/// An engine schematic: its numbers and symbols in reading order, and which of them are adjacent,
+/// including diagonally. Everything that isn't a digit or `.` is a symbol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schematic {
+    numbers: Vec<Number>,
+    symbols: Vec<Symbol>,
+    numbers_by_symbol: Vec<Vec<usize>>,
+    symbols_by_number: Vec<Vec<usize>>,
+}
+
+/// Whether every line of a schematic must be as long as the first.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Shape {
+    #[default]
+    Rectangular,
+    /// Lines may have any length, as if padded with `.` to the longest.
+    Ragged,
+}
+
+/// Any character can be a symbol, except for whitespace and control characters.
+fn cell(c: char) -> Option<char> {
+    (!c.is_whitespace() && !c.is_control()).then_some(c)
+}
+
+const EXPECTED_CELL: &str = "a digit, '.' or a symbol";
+
+impl Schematic {
+    pub fn parse(input: &str) -> Result<Schematic, ParseError> {
+        Schematic::parse_shaped(input, Shape::Rectangular)
+    }
+
+    pub fn parse_shaped(input: &str, shape: Shape) -> Result<Schematic, ParseError> {
+        match shape {
+            Shape::Rectangular => Schematic::from_grid(&Grid::parse(input, cell, EXPECTED_CELL)?),
+            Shape::Ragged => {
+                let rows = input
+                    .trim_end_matches(['\n', '\r'])
+                    .lines()
+                    .map(|line| {
+                        line.char_indices()
+                            .map(|(i, c)| {
+                                cell(c).ok_or_else(|| {
+                                    ParseError::at_slice(input, &line[i..], EXPECTED_CELL)
+                                })
+                            })
+                            .collect::<Result<Vec<_>, _>>()
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Schematic::from_rows(rows.iter().map(Vec::as_slice))
+            }
+        }
+    }
+
+    pub fn from_grid(grid: &Grid<char>) -> Result<Schematic, ParseError> {
+        Schematic::from_rows(grid.rows())
+    }
+
+    fn from_rows<'a>(rows: impl Iterator<Item = &'a [char]>) -> Result<Schematic, ParseError> {
+        let mut numbers = vec![];
+        let mut symbols = vec![];
+        for (row, cells) in rows.enumerate() {
+            let mut col = 0;
+            while col < cells.len() {
+                if !cells[col].is_ascii_digit() {
+                    if cells[col] != '.' {
+                        symbols.push(Symbol {
+                            symbol: cells[col],
+                            pos: (row, col),
+                        });
+                    }
+                    col += 1;
+                    continue;
+                }
+
+                let start = col;
+                let mut value = 0u64;
+                while let Some(digit) = cells.get(col).and_then(|c| c.to_digit(10)) {
+                    value = value
+                        .checked_mul(10)
+                        .and_then(|value| value.checked_add(digit as u64))
+                        .ok_or_else(|| ParseError {
+                            location: Some(Location {
+                                line: row + 1,
+                                column: start + 1,
+                                source_line: cells.iter().collect(),
+                            }),
+                            ..ParseError::expected("a number that fits in 64 bits")
+                        })?;
+                    col += 1;
+                }
+                numbers.push(Number {
+                    value,
+                    span: Span {
+                        row,
+                        start,
+                        end: col,
+                    },
+                });
+            }
+        }
+
+        let number_at = numbers
+            .iter()
+            .enumerate()
+            .flat_map(|(i, number)| number.span.positions().map(move |pos| (pos, i)))
+            .collect::<FxHashMap<_, _>>();
+        let numbers_by_symbol = symbols
+            .iter()
+            .map(|symbol| {
+                let mut adjacent = DIRECTIONS_8
+                    .iter()
+                    .filter_map(|&(d_row, d_col)| {
+                        let pos = (
+                            symbol.pos.0.checked_add_signed(d_row)?,
+                            symbol.pos.1.checked_add_signed(d_col)?,
+                        );
+                        number_at.get(&pos).copied()
+                    })
+                    .collect::<Vec<_>>();
+                adjacent.sort_unstable();
+                adjacent.dedup();
+                adjacent
+            })
+            .collect::<Vec<_>>();
+        let mut symbols_by_number = vec![vec![]; numbers.len()];
+        for (symbol, adjacent) in numbers_by_symbol.iter().enumerate() {
+            for &number in adjacent {
+                symbols_by_number[number].push(symbol);
+            }
+        }
+
+        Ok(Schematic {
+            numbers,
+            symbols,
+            numbers_by_symbol,
+            symbols_by_number,
+        })
+    }
+
+    pub fn numbers(&self) -> &[Number] {
+        &self.numbers
+    }
+
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    /// The numbers next to the symbol with index `symbol`.
+    pub fn adjacent_numbers(&self, symbol: usize) -> impl Iterator<Item = &Number> {
+        self.numbers_by_symbol[symbol]
+            .iter()
+            .map(|&i| &self.numbers[i])
+    }
+
+    /// The indices of the numbers next to the symbol with index `symbol`.
+    pub fn adjacent_number_indices(&self, symbol: usize) -> &[usize] {
+        &self.numbers_by_symbol[symbol]
+    }
+
+    /// The symbols next to the number with index `number`.
+    pub fn adjacent_symbols(&self, number: usize) -> impl Iterator<Item = &Symbol> {
+        self.symbols_by_number[number]
+            .iter()
+            .map(|&i| &self.symbols[i])
+    }
+
+    /// The numbers next to at least one symbol.
+    pub fn part_numbers(&self) -> impl Iterator<Item = &Number> {
+        self.numbers
+            .iter()
+            .zip(&self.symbols_by_number)
+            .filter(|(_, symbols)| !symbols.is_empty())
+            .map(|(number, _)| number)
+    }
+
+    /// The numbers next to no symbol.
+    pub fn orphans(&self) -> impl Iterator<Item = &Number> {
+        self.numbers
+            .iter()
+            .zip(&self.symbols_by_number)
+            .filter(|(_, symbols)| symbols.is_empty())
+            .map(|(number, _)| number)
+    }
+
+    /// The connected groups of numbers and symbols, ordered by their first number, then by their
+    /// symbol for lone symbols. Orphans and lone symbols are components of their own.
+    pub fn components(&self) -> Vec<Component> {
+        #[derive(Clone, Copy)]
+        enum Node {
+            Number(usize),
+            Symbol(usize),
+        }
+
+        let mut seen_numbers = vec![false; self.numbers.len()];
+        let mut seen_symbols = vec![false; self.symbols.len()];
+        let starts = (0..self.numbers.len())
+            .map(Node::Number)
+            .chain((0..self.symbols.len()).map(Node::Symbol));
+        let mut components = vec![];
+        for start in starts {
+            let mut component = Component::default();
+            let mut queue = VecDeque::from([start]);
+            while let Some(node) = queue.pop_front() {
+                match node {
+                    Node::Number(i) if !seen_numbers[i] => {
+                        seen_numbers[i] = true;
+                        component.numbers.push(i);
+                        queue.extend(self.symbols_by_number[i].iter().map(|&s| Node::Symbol(s)));
+                    }
+                    Node::Symbol(i) if !seen_symbols[i] => {
+                        seen_symbols[i] = true;
+                        component.symbols.push(i);
+                        queue.extend(self.numbers_by_symbol[i].iter().map(|&n| Node::Number(n)));
+                    }
+                    _ => {}
+                }
+            }
+            if !component.numbers.is_empty() || !component.symbols.is_empty() {
+                component.numbers.sort_unstable();
+                component.symbols.sort_unstable();
+                components.push(component);
+            }
+        }
+        components
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+    use indoc::indoc;
+
+    const SYMBOLS: [char; 7] = ['*', '#', '+', '$', '€', '★', '§'];
+
+    fn random_rows(rng: &mut Rng) -> Vec<Vec<char>> {
+        (0..rng.range(0..=8))
+            .map(|_| {
+                (0..rng.range(0..=12))
+                    .map(|_| match rng.below(10) {
+                        0..=4 => '.',
+                        5..=7 => char::from_digit(rng.below(10) as u32, 10).unwrap(),
+                        _ => SYMBOLS[rng.below(SYMBOLS.len() as u64) as usize],
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Checks every number against every symbol, without looking anything up by position.
+    fn naive_adjacency(rows: &[Vec<char>]) -> Vec<(u64, Vec<Pos>)> {
+        let mut numbers = vec![];
+        for (row, cells) in rows.iter().enumerate() {
+            let text = cells
+                .iter()
+                .map(|&c| if c.is_ascii_digit() { c } else { ' ' })
+                .collect::<String>();
+            let mut col = 0;
+            for word in text.split(' ') {
+                if !word.is_empty() {
+                    numbers.push((word.parse::<u64>().unwrap(), row, col, col + word.len()));
+                }
+                col += word.len() + 1;
+            }
+        }
+
+        numbers
+            .into_iter()
+            .map(|(value, row, start, end)| {
+                let mut symbols = vec![];
+                for (s_row, cells) in rows.iter().enumerate() {
+                    for (s_col, &c) in cells.iter().enumerate() {
+                        let is_symbol = !c.is_ascii_digit() && c != '.';
+                        if is_symbol
+                            && s_row + 1 >= row
+                            && s_row <= row + 1
+                            && s_col + 1 >= start
+                            && s_col <= end
+                        {
+                            symbols.push((s_row, s_col));
+                        }
+                    }
+                }
+                (value, symbols)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test() {
+        let schematic = Schematic::parse_shaped(
+            indoc! {"
+                12
+                .€..
+                ......7
+                ★3
+            "},
+            Shape::Ragged,
+        )
+        .unwrap();
+        assert_eq!(
+            schematic
+                .part_numbers()
+                .map(|n| n.value)
+                .collect::<Vec<_>>(),
+            [12, 3]
+        );
+        assert_eq!(
+            schematic.orphans().map(|n| n.value).collect::<Vec<_>>(),
+            [7]
+        );
+        assert_eq!(schematic.symbols()[1].pos, (3, 0));
+
+        let err = Schematic::parse("12\n.€..\n").err().unwrap();
+        assert_eq!(err.expected, "a row of 2 cells, like the first");
+        let err = Schematic::parse_shaped("1 2\n", Shape::Ragged)
+            .err()
+            .unwrap();
+        assert_eq!(err.expected, EXPECTED_CELL);
+        assert_eq!(err.location.unwrap().column, 2);
+    }
+
+    #[test]
+    fn test_against_naive_scan() {
+        let mut rng = Rng::new(3);
+        for _ in 0..500 {
+            let rows = random_rows(&mut rng);
+            let input = rows
+                .iter()
+                .map(|row| row.iter().collect::<String>() + "\n")
+                .collect::<String>();
+            let schematic = Schematic::parse_shaped(&input, Shape::Ragged).unwrap();
+
+            let adjacency = (0..schematic.numbers().len())
+                .map(|i| {
+                    (
+                        schematic.numbers()[i].value,
+                        schematic.adjacent_symbols(i).map(|s| s.pos).collect(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(adjacency, naive_adjacency(&rows), "{input}");
+
+            // padding to a rectangle with '.' changes nothing
+            let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+            let padded = rows
+                .iter()
+                .map(|row| {
+                    format!(
+                        "{}{}\n",
+                        row.iter().collect::<String>(),
+                        ".".repeat(width - row.len())
+                    )
+                })
+                .collect::<String>();
+            assert_eq!(Schematic::parse(&padded).unwrap(), schematic, "{input}");
+        }
+    }
+}
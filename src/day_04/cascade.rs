@@ -0,0 +1,183 @@
+use num::Unsigned;
+
+/// What happens to copies won of cards past the end of the deck.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// They are never won.
+    #[default]
+    Drop,
+    /// They are copies of the cards at the start of the deck instead. Those cards have already been
+    /// scratched, so the copies are counted but don't win anything, and no card wins more than
+    /// one copy of any other card at a time.
+    Wrap,
+}
+
+/// A variant of the rule that every card wins copies of the cards after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CascadeRule<C> {
+    pub overflow: Overflow,
+    /// The most copies any one card can have, counting the one it starts with; anything won
+    /// beyond that is lost.
+    pub max_copies: Option<C>,
+}
+
+impl<C: Clone + Ord> CascadeRule<C> {
+    fn cap(&self, copies: C) -> C {
+        match &self.max_copies {
+            Some(max) => copies.min(max.clone()),
+            None => copies,
+        }
+    }
+}
+
+impl<C> Default for CascadeRule<C> {
+    fn default() -> Self {
+        CascadeRule {
+            overflow: Overflow::Drop,
+            max_copies: None,
+        }
+    }
+}
+
+/// How many copies of a card there are in the end, and where they came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardCopies<C> {
+    pub copies: C,
+    /// The cards that won copies of this one, in the order they did, and how many each added.
+    pub contributors: Vec<(usize, C)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trace<C> {
+    pub cards: Vec<CardCopies<C>>,
+}
+
+impl<C: Unsigned + Clone> Trace<C> {
+    pub fn total(&self) -> C {
+        self.cards
+            .iter()
+            .fold(C::zero(), |total, card| total + card.copies.clone())
+    }
+}
+
+/// The cards won by a card with `matches` matches at `i` in a deck of `n` cards.
+fn targets(i: usize, matches: u32, n: usize, overflow: Overflow) -> impl Iterator<Item = usize> {
+    let steps = match overflow {
+        Overflow::Drop => (matches as usize).min(n - 1 - i),
+        Overflow::Wrap => (matches as usize).min(n - 1),
+    };
+    (1..=steps).map(move |step| (i + step) % n)
+}
+
+/// Scratches every card in order, starting with one of each, where a card with `matches[i]`
+/// matches wins a copy of that many following cards for every copy of it there is.
+/// The counts double with every card in the worst case, so use a big integer type such as
+/// [`num::BigUint`] for decks where they could pass `u64::MAX`.
+pub fn simulate<C: Unsigned + Clone + Ord>(matches: &[u32], rule: &CascadeRule<C>) -> Trace<C> {
+    let n = matches.len();
+    let mut cards = vec![
+        CardCopies {
+            copies: rule.cap(C::one()),
+            contributors: vec![],
+        };
+        n
+    ];
+
+    for (i, &m) in matches.iter().enumerate() {
+        let won = cards[i].copies.clone();
+        for j in targets(i, m, n, rule.overflow) {
+            let card = &mut cards[j];
+            // counts start within the cap, so capping never takes copies away
+            let copies = rule.cap(card.copies.clone() + won.clone());
+            let added = copies.clone() - card.copies.clone();
+            if !added.is_zero() {
+                card.copies = copies;
+                card.contributors.push((i, added));
+            }
+        }
+    }
+
+    Trace { cards }
+}
+
+/// The copies of each card that [`simulate`] ends up with, without keeping track of where they
+/// came from.
+pub fn copies<C: Unsigned + Clone + Ord>(matches: &[u32], rule: &CascadeRule<C>) -> Vec<C> {
+    let n = matches.len();
+    let mut copies = vec![rule.cap(C::one()); n];
+    for (i, &m) in matches.iter().enumerate() {
+        let won = copies[i].clone();
+        for j in targets(i, m, n, rule.overflow) {
+            copies[j] = rule.cap(copies[j].clone() + won.clone());
+        }
+    }
+    copies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num::{BigUint, One};
+
+    #[test]
+    fn test() {
+        let matches = [4, 2, 2, 1, 0, 0];
+        let trace = simulate::<u64>(&matches, &CascadeRule::default());
+        assert_eq!(
+            trace.cards.iter().map(|c| c.copies).collect::<Vec<_>>(),
+            [1, 2, 4, 8, 14, 1]
+        );
+        assert_eq!(trace.cards[4].contributors, [(0, 1), (2, 4), (3, 8)]);
+        assert_eq!(trace.total(), 30);
+        assert_eq!(
+            copies::<u64>(&matches, &CascadeRule::default()),
+            [1, 2, 4, 8, 14, 1]
+        );
+
+        let capped = CascadeRule {
+            max_copies: Some(3),
+            ..CascadeRule::default()
+        };
+        let trace = simulate::<u64>(&matches, &capped);
+        assert_eq!(
+            trace.cards.iter().map(|c| c.copies).collect::<Vec<_>>(),
+            [1, 2, 3, 3, 3, 1]
+        );
+        assert_eq!(trace.cards[2].contributors, [(0, 1), (1, 1)]);
+        assert_eq!(copies(&matches, &capped), [1, 2, 3, 3, 3, 1]);
+
+        // a cap below one leaves every card without copies
+        let none = CascadeRule {
+            max_copies: Some(BigUint::from(0u32)),
+            ..CascadeRule::default()
+        };
+        let trace = simulate(&matches, &none);
+        assert!(trace.cards.iter().all(|c| c.contributors.is_empty()));
+        assert_eq!(trace.total(), BigUint::from(0u32));
+        assert_eq!(copies(&matches, &none), vec![BigUint::from(0u32); 6]);
+
+        let single = CascadeRule {
+            max_copies: Some(1),
+            ..CascadeRule::default()
+        };
+        assert_eq!(simulate::<u64>(&matches, &single).total(), 6);
+        assert_eq!(copies::<u64>(&matches, &single), [1; 6]);
+
+        let wrap = CascadeRule {
+            overflow: Overflow::Wrap,
+            ..CascadeRule::default()
+        };
+        let trace = simulate::<u64>(&[0, 0, 5], &wrap);
+        assert_eq!(
+            trace.cards.iter().map(|c| c.copies).collect::<Vec<_>>(),
+            [2, 2, 1]
+        );
+        assert_eq!(copies::<u64>(&[0, 0, 5], &wrap), [2, 2, 1]);
+        assert!(copies::<u64>(&[], &wrap).is_empty());
+
+        // every card wins a copy of all the cards after it, so card `i` ends up with 2^i copies
+        let matches = (0..200).rev().collect::<Vec<u32>>();
+        let trace = simulate::<BigUint>(&matches, &CascadeRule::default());
+        assert_eq!(trace.total(), (BigUint::one() << 200u32) - 1u32);
+    }
+}
@@ -1,125 +1,159 @@
 use nom::{
-    bytes::complete::tag,
-    character::complete::{char, digit1, line_ending, space0, space1, u8},
+    character::complete::{char, digit1, space0, space1, u32},
+    combinator::{cut, peek, verify},
+    error::context,
     multi::separated_list0,
-    sequence::tuple,
+    sequence::{delimited, preceded, tuple},
     Parser,
 };
 
+use num::BigUint;
+
+use crate::{
+    bitset::BitSet,
+    parse::{parse_lines, tag, IResult, ParseError},
+    solution::{Answer, Error, SolveError, Solution},
+};
+
+pub mod cascade;
+pub mod scoring;
+
+pub use cascade::{copies, simulate, CardCopies, CascadeRule, Overflow, Trace};
+pub use scoring::{Capped, CardScore, Cascade, Doubling, Linear, ScoringRule};
+
+pub type Numbers = BitSet<4>;
+
 pub struct Input {
-    cards: Vec<Card>,
+    pub cards: Vec<Card>,
 }
 
 pub struct Card {
-    winners: Vec<u8>,
-    mine: Vec<u8>,
+    pub id: u32,
+    pub winners: Numbers,
+    pub mine: Numbers,
+}
+
+impl Card {
+    /// How many of my numbers are winners.
+    pub fn matches(&self) -> u32 {
+        (self.winners & self.mine).len()
+    }
+}
+
+impl Input {
+    pub fn matches(&self) -> Vec<u32> {
+        self.cards.iter().map(Card::matches).collect()
+    }
+
+    pub fn score(&self, rule: &impl ScoringRule) -> u64 {
+        rule.score(&self.matches())
+    }
 }
 
-pub type IResult<'a, T> = nom::IResult<&'a str, T>;
+fn numbers(input: &str) -> IResult<'_, Numbers> {
+    separated_list0(
+        space1,
+        // once there is a number it has to fit
+        preceded(
+            peek(digit1),
+            cut(context(
+                "a number below 256",
+                verify(u32, |&n| (n as usize) < Numbers::CAPACITY),
+            )),
+        ),
+    )
+    .map(|numbers| numbers.into_iter().map(|n| n as usize).collect())
+    .parse(input)
+}
 
-pub fn input_generator(input: &str) -> Input {
-    let cards: IResult<Vec<Card>> = separated_list0(
-        line_ending,
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let cards = parse_lines(
+        input,
+        input,
         tuple((
-            tuple((tag("Card"), space1, digit1, tag(":"), space0)),
-            separated_list0(space1, u8),
+            delimited(tuple((tag("Card"), space1)), u32, tuple((tag(":"), space0))),
+            numbers,
             tuple((space0, char('|'), space0)),
-            separated_list0(space1, u8),
+            numbers,
         ))
-        .map(|(_, mut winners, _, mut mine)| {
-            winners.sort();
-            mine.sort();
-            Card { winners, mine }
-        }),
-    )(input);
-    let cards = cards.expect("failed to parse input");
+        .map(|(id, winners, _, mine)| Card { id, winners, mine }),
+    )?;
 
-    assert!(cards.0.trim().is_empty(), "failed to parse entire input");
+    Ok(Input { cards })
+}
 
-    Input { cards: cards.1 }
+pub fn part_1(input: &Input) -> u64 {
+    input.score(&Doubling)
 }
 
-pub fn part_1(input: &Input) -> u32 {
-    input
-        .cards
-        .iter()
-        .map(|card| {
-            // TODO refactor into iterator of winners and share with part 2
-            let mut winners = card.winners.iter().peekable();
-            card.mine.iter().fold(0, |mut score, num| {
-                loop {
-                    match winners.peek() {
-                        None => break,
-                        Some(w) if *w == num => {
-                            score = (2 * score).max(1);
-                            break;
-                        }
-                        Some(w) if *w > num => break,
-                        _ => {
-                            winners.next();
-                        }
-                    }
-                }
-                score
-            })
-        })
-        .sum()
+pub fn part_2(input: &Input) -> Result<u64, SolveError> {
+    let cards = copies::<BigUint>(&input.matches(), &CascadeRule::default())
+        .into_iter()
+        .sum::<BigUint>();
+    cards
+        .try_into()
+        .map_err(|_| SolveError::Overflow("the number of cards".into()))
 }
 
-pub fn part_2(input: &Input) -> u32 {
-    let mut counts = vec![1; input.cards.len()];
-
-    input
-        .cards
-        .iter()
-        .enumerate()
-        .map(|(i, card)| {
-            // TODO refactor into iterator of winners and share with part 2
-            let mut winners = card.winners.iter().peekable();
-            let num_winners = card
-                .mine
-                .iter()
-                .filter(|num| {
-                    loop {
-                        match winners.peek() {
-                            None => break,
-                            Some(w) if w == num => {
-                                return true;
-                            }
-                            Some(w) if *w > num => break,
-                            _ => {
-                                winners.next();
-                            }
-                        }
-                    }
-                    false
-                })
-                .count();
-
-            ((i + 1)..(i + 1 + num_winners)).for_each(|j| counts[j] += counts[i]);
-            counts[i]
-        })
-        .sum()
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input = Input;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part_1(input: &Input) -> Result<Answer, Error> {
+        Ok(part_1(input).into())
+    }
+
+    fn part_2(input: &Input) -> Result<Answer, Error> {
+        Ok(part_2(input)?.into())
+    }
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use indoc::indoc;
 
+    pub(crate) const EXAMPLE: &str = indoc! {
+        "
+        Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+        Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+        Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+        Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+        Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+        Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+        "
+    };
+
     #[test]
     fn test() {
-        let input = input_generator(indoc! {
-            "
-            Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-            Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
-            Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
-            Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
-            Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-            Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
-            "
-        });
+        let input = input_generator(EXAMPLE).unwrap();
         assert_eq!(part_1(&input), 13);
-        assert_eq!(part_2(&input), 30);
+        assert_eq!(part_2(&input), Ok(30));
+        assert_eq!(input.matches(), [4, 2, 2, 1, 0, 0]);
+        assert_eq!(input.cards[2].id, 3);
+
+        let input = input_generator("Card 1: 100 127 | 127 100 3\n").unwrap();
+        assert_eq!(input.matches(), [2]);
+        let input = input_generator("Card 1: 200 5 | 200 7\nCard 2: 128 255 | 255 128\n").unwrap();
+        assert_eq!(input.matches(), [1, 2]);
+        let err = input_generator("Card 1: 100 256 | 1\n").err().unwrap();
+        assert_eq!(err.expected, "a number below 256");
+        assert_eq!(err.location.unwrap().column, 13);
+
+        // every card wins a copy of all the cards after it, for 2^70 - 1 cards in the end
+        let deck = (0..70u32)
+            .map(|i| {
+                let numbers = (i..69).map(|n| n.to_string()).collect::<Vec<_>>().join(" ");
+                format!("Card {}: {numbers} | {numbers}\n", i + 1)
+            })
+            .collect::<String>();
+        let input = input_generator(&deck).unwrap();
+        assert_eq!(input.score(&Cascade), u64::MAX);
+        assert!(part_2(&input).is_err());
     }
 }
@@ -0,0 +1,103 @@
+use num::BigUint;
+
+use super::cascade::{copies, CascadeRule};
+
+/// Scores a single card from how many of its numbers match.
+pub trait CardScore {
+    fn card_score(&self, matches: u32) -> u64;
+}
+
+/// Scores a whole pile of cards from how many numbers match on each, in order.
+pub trait ScoringRule {
+    fn score(&self, matches: &[u32]) -> u64;
+}
+
+/// Cards that are scored on their own are worth the sum of their scores.
+impl<R: CardScore> ScoringRule for R {
+    fn score(&self, matches: &[u32]) -> u64 {
+        matches.iter().map(|&m| self.card_score(m)).sum()
+    }
+}
+
+impl<F: Fn(u32) -> u64> CardScore for F {
+    fn card_score(&self, matches: u32) -> u64 {
+        self(matches)
+    }
+}
+
+/// One point for the first match, doubled for each one after it, saturating at `u64::MAX`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Doubling;
+
+impl CardScore for Doubling {
+    fn card_score(&self, matches: u32) -> u64 {
+        match matches {
+            0 => 0,
+            m => 1u64.checked_shl(m - 1).unwrap_or(u64::MAX),
+        }
+    }
+}
+
+/// The same number of points for every match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Linear {
+    pub points: u64,
+}
+
+impl CardScore for Linear {
+    fn card_score(&self, matches: u32) -> u64 {
+        self.points * matches as u64
+    }
+}
+
+/// Another rule's score, but no more than `cap` for any one card.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capped<R> {
+    pub rule: R,
+    pub cap: u64,
+}
+
+impl<R: CardScore> CardScore for Capped<R> {
+    fn card_score(&self, matches: u32) -> u64 {
+        self.rule.card_score(matches).min(self.cap)
+    }
+}
+
+/// Each card wins a copy of as many of the cards after it as it has matches, and the score is the
+/// number of cards in the end, saturating at `u64::MAX`. See [`simulate`](super::simulate) for the
+/// copies of each card and for variants.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Cascade;
+
+impl ScoringRule for Cascade {
+    fn score(&self, matches: &[u32]) -> u64 {
+        let cards = copies::<BigUint>(matches, &CascadeRule::default())
+            .into_iter()
+            .sum::<BigUint>();
+        cards.try_into().unwrap_or(u64::MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let matches = [4, 2, 2, 1, 0, 0];
+        assert_eq!(Doubling.score(&matches), 13);
+        assert_eq!(Doubling.card_score(70), u64::MAX);
+        assert_eq!(Cascade.score(&matches), 30);
+        assert_eq!(Cascade.score(&(0..70).rev().collect::<Vec<_>>()), u64::MAX);
+        assert_eq!(Linear { points: 3 }.score(&matches), 27);
+        assert_eq!(
+            Capped {
+                rule: Doubling,
+                cap: 2
+            }
+            .score(&matches),
+            7
+        );
+        assert_eq!((|m: u32| (m * m) as u64).score(&matches), 25);
+    }
+}
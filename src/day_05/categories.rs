@@ -0,0 +1,196 @@
+use std::collections::VecDeque;
+
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use super::{Input, Map, RangeMap};
+use crate::solution::SolveError;
+
+impl Input {
+    /// The fewest maps that lead from category `from` to category `to`, by index, in the order
+    /// they apply. The maps can appear in the almanac in any order.
+    pub fn path(&self, from: &str, to: &str) -> Result<Vec<usize>, SolveError> {
+        let mut reached_by = FxHashMap::<&str, Option<usize>>::default();
+        reached_by.insert(from, None);
+        let mut queue = VecDeque::from([from]);
+        while let Some(category) = queue.pop_front() {
+            if category == to {
+                let mut path = vec![];
+                let mut category = to;
+                while let Some(i) = reached_by[category] {
+                    path.push(i);
+                    category = &self.maps[i].source;
+                }
+                path.reverse();
+                return Ok(path);
+            }
+            for (i, map) in self.maps.iter().enumerate() {
+                if map.source == category && !reached_by.contains_key(map.dest.as_str()) {
+                    reached_by.insert(&map.dest, Some(i));
+                    queue.push_back(&map.dest);
+                }
+            }
+        }
+        Err(SolveError::Missing(
+            format!("maps leading from {from} to {to}").into(),
+        ))
+    }
+
+    /// The maps from category `from` to category `to`, collapsed into one.
+    pub fn conversion(&self, from: &str, to: &str) -> Result<RangeMap, SolveError> {
+        Ok(self
+            .path(from, to)?
+            .into_iter()
+            .fold(RangeMap::identity(), |conversion, i| {
+                conversion.compose(&RangeMap::new(&self.maps[i].conversions))
+            }))
+    }
+
+    /// Maps that lead from one of `categories` back to where they started, by index, if there are
+    /// any. Unlike [`Input::cycle`], this ignores cycles that only go through other categories.
+    pub fn cycle_through(&self, categories: &[&str]) -> Option<Vec<usize>> {
+        self.maps
+            .iter()
+            .enumerate()
+            .filter(|(_, map)| categories.contains(&map.source.as_str()))
+            .find_map(|(i, map)| {
+                let back = self.path(&map.dest, &map.source).ok()?;
+                Some(std::iter::once(i).chain(back).collect())
+            })
+    }
+
+    /// Maps that lead from a category back to itself, by index, if there are any.
+    pub fn cycle(&self) -> Option<Vec<usize>> {
+        let mut done = FxHashSet::default();
+        for map in &self.maps {
+            if !done.contains(map.source.as_str()) {
+                let mut visiting = vec![map.source.as_str()];
+                let cycle = visit(&self.maps, &mut visiting, &mut vec![], &mut done);
+                if cycle.is_some() {
+                    return cycle;
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Depth-first search from the last of the categories in `visiting`, where `path[i]` is the map
+/// from `visiting[i]` to `visiting[i + 1]`.
+fn visit<'a>(
+    maps: &'a [Map],
+    visiting: &mut Vec<&'a str>,
+    path: &mut Vec<usize>,
+    done: &mut FxHashSet<&'a str>,
+) -> Option<Vec<usize>> {
+    let category = *visiting.last()?;
+    for (i, map) in maps.iter().enumerate() {
+        if map.source != category || done.contains(map.dest.as_str()) {
+            continue;
+        }
+        if let Some(start) = visiting.iter().position(|&c| c == map.dest) {
+            path.push(i);
+            return Some(path[start..].to_vec());
+        }
+        visiting.push(&map.dest);
+        path.push(i);
+        let cycle = visit(maps, visiting, path, done);
+        if cycle.is_some() {
+            return cycle;
+        }
+        visiting.pop();
+        path.pop();
+    }
+    done.insert(category);
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day_05::{input_generator, part_1, IssueKind};
+    use crate::solution::{Error, SolveError};
+    use indoc::indoc;
+
+    #[test]
+    fn test() {
+        let input = input_generator(indoc! {"
+            seeds: 1 2 3
+
+            soil-to-water map:
+            10 0 5
+
+            water-to-location map:
+            100 10 5
+
+            seed-to-soil map:
+            2 1 2
+
+            seed-to-fertilizer map:
+            0 0 1
+        "})
+        .unwrap();
+        assert_eq!(input.path("seed", "location"), Ok(vec![2, 0, 1]));
+        assert_eq!(input.path("soil", "soil"), Ok(vec![]));
+        assert_eq!(input.conversion("soil", "location").unwrap().apply(2), 102);
+        assert_eq!(part_1(&input), Ok(102));
+        assert_eq!(input.cycle(), None);
+
+        assert_eq!(
+            input.path("fertilizer", "location"),
+            Err(SolveError::Missing(
+                "maps leading from fertilizer to location".into()
+            ))
+        );
+
+        let input = input_generator(indoc! {"
+            seeds: 1
+
+            seed-to-soil map:
+            0 0 1
+
+            soil-to-water map:
+            0 0 1
+
+            water-to-soil map:
+            0 0 1
+
+            soil-to-location map:
+            0 0 1
+        "})
+        .unwrap();
+        assert_eq!(input.cycle(), Some(vec![1, 2]));
+        assert_eq!(input.cycle_through(&["seed"]), None);
+        assert_eq!(input.cycle_through(&["seed", "soil"]), Some(vec![1, 2]));
+        let issue = input.validate().pop().unwrap();
+        assert_eq!((issue.line, issue.kind), (9, IssueKind::Cycle { line: 6 }));
+        let Err(Error::Parse(err)) = part_1(&input) else {
+            panic!("a cycle should be reported at its position");
+        };
+        assert_eq!(
+            err.expected,
+            "a map that doesn't lead back to line 6's in the water-to-soil map"
+        );
+
+        // a cycle that the seeds never reach doesn't matter
+        let input = input_generator(indoc! {"
+            seeds: 1
+
+            seed-to-location map:
+            5 0 2
+
+            water-to-light map:
+            0 0 1
+
+            light-to-water map:
+            0 0 1
+        "})
+        .unwrap();
+        assert_eq!(input.cycle(), Some(vec![1, 2]));
+        assert_eq!(part_1(&input), Ok(6));
+
+        let err = input_generator("seeds: 1\n\nseed-to-soil:\n0 0 1\n")
+            .err()
+            .unwrap();
+        assert_eq!(err.expected, "a map header like 'seed-to-soil map:'");
+        assert_eq!(err.location.unwrap().line, 3);
+    }
+}
@@ -1,26 +1,118 @@
+use std::ops::Range;
+
 use itertools::Itertools;
 use nom::{
-    bytes::complete::{tag, take_till},
+    bytes::complete::take_while1,
     character::complete::{char, line_ending, space1, u64},
+    combinator::cut,
+    error::context,
     multi::{many1, separated_list0},
-    sequence::{preceded, tuple},
+    sequence::{preceded, separated_pair, terminated, tuple},
     Parser,
 };
 
+use crate::{
+    parse::{finish, tag, IResult, ParseError},
+    solution::{Answer, Error, SolveError, Solution},
+};
+
+pub mod categories;
+pub mod range_map;
+pub mod validate;
+
+pub use range_map::RangeMap;
+pub use validate::{Issue, IssueKind};
+
 pub struct Input {
-    seeds: Vec<u64>,
-    maps: Vec<Vec<Conversion>>,
+    pub seeds: Vec<u64>,
+    pub maps: Vec<Map>,
+}
+
+/// One of the almanac's maps, from values of the `source` category to the `dest` category, with
+/// its conversions on the lines after its header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Map {
+    pub source: String,
+    pub dest: String,
+    /// 1-based line number of the header.
+    pub line: usize,
+    pub conversions: Vec<Conversion>,
+}
+
+impl Map {
+    /// The header without its colon, such as `seed-to-soil map`.
+    pub fn header(&self) -> String {
+        format!("{}-to-{} map", self.source, self.dest)
+    }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Conversion {
-    dest_start: u64,
-    source_start: u64,
-    len: u64,
+    pub dest_start: u64,
+    pub source_start: u64,
+    pub len: u64,
 }
 
-pub fn parse_map(input: &str) -> nom::IResult<&str, Vec<Conversion>> {
-    preceded(
-        tuple((take_till(|c| matches!(c, '\n' | '\r')), line_ending)),
+impl Input {
+    /// The maps from seeds to locations, collapsed into one.
+    pub fn almanac(&self) -> Result<RangeMap, SolveError> {
+        self.conversion("seed", "location")
+    }
+
+    /// The parts of the seed ranges that end up in one of the `locations`.
+    pub fn seeds_landing_in(
+        &self,
+        locations: &[Range<u64>],
+    ) -> Result<Vec<Range<u64>>, SolveError> {
+        let seeds = self.seed_ranges();
+        Ok(self
+            .almanac()?
+            .preimage(locations)
+            .into_iter()
+            .flat_map(|range| {
+                seeds
+                    .iter()
+                    .map(move |seeds| range.start.max(seeds.start)..range.end.min(seeds.end))
+            })
+            .filter(|range| !range.is_empty())
+            .collect())
+    }
+
+    /// The seeds read as pairs of a start and a length, cut short at `u64::MAX`.
+    pub fn seed_ranges(&self) -> Vec<Range<u64>> {
+        self.seeds
+            .iter()
+            .tuples()
+            .map(|(&start, &len)| start..start.saturating_add(len))
+            .collect()
+    }
+}
+
+fn category(input: &str) -> IResult<'_, &str> {
+    take_while1(|c: char| c.is_alphanumeric() || c == '_')(input)
+}
+
+/// A map's source and destination categories, and its conversions.
+pub fn parse_map(input: &str) -> IResult<'_, ((&str, &str), Vec<Conversion>)> {
+    tuple((
+        // a category can only start a header
+        terminated(
+            separated_pair(
+                category,
+                cut(context(
+                    "a map header like 'seed-to-soil map:'",
+                    tag("-to-"),
+                )),
+                category,
+            ),
+            tuple((
+                cut(context(
+                    "a map header like 'seed-to-soil map:'",
+                    tag(" map:"),
+                )),
+                line_ending,
+            )),
+        ),
         separated_list0(
             line_ending,
             tuple((u64, char(' '), u64, char(' '), u64)).map(
@@ -31,148 +123,141 @@ pub fn parse_map(input: &str) -> nom::IResult<&str, Vec<Conversion>> {
                 },
             ),
         ),
-    )(input)
+    ))(input)
 }
 
-pub fn parse_input(input: &str) -> nom::IResult<&str, Input> {
+pub fn parse_input<'a>(input: &'a str) -> IResult<'a, Input> {
+    let full = input;
     let (input, seeds) = preceded(tag("seeds: "), separated_list0(space1, u64))(input)?;
     let (input, maps) = preceded(
         many1(line_ending),
-        separated_list0(many1(line_ending), parse_map),
+        separated_list0(many1(line_ending), |rest: &'a str| {
+            let line = full[..full.len() - rest.len()].matches('\n').count() + 1;
+            parse_map
+                .map(|((source, dest), conversions)| Map {
+                    source: source.to_owned(),
+                    dest: dest.to_owned(),
+                    line,
+                    conversions,
+                })
+                .parse(rest)
+        }),
     )(input)?;
 
     Ok((input, Input { seeds, maps }))
 }
 
-pub fn input_generator(input: &str) -> Input {
-    let res = parse_input(input);
-    let (remaining, out) = res.expect("failed to parse");
-    assert!(remaining.trim().is_empty(), "didn't parse entire input");
-    out
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    finish(input, parse_input)
 }
 
-pub fn part_1(input: &Input) -> u64 {
+pub fn part_1(input: &Input) -> Result<u64, Error> {
+    input.check()?;
+    let almanac = input.almanac()?;
     input
         .seeds
         .iter()
-        .map(|&seed| {
-            input.maps.iter().fold(seed, |val, map| {
-                map.iter()
-                    .find_map(|conv| {
-                        if (val >= conv.source_start) && (val < conv.source_start + conv.len) {
-                            Some(conv.dest_start + val - conv.source_start)
-                        } else {
-                            None
-                        }
-                    })
-                    .unwrap_or(val)
-            })
-        })
+        .map(|&seed| almanac.apply(seed))
         .min()
-        .expect("no mappings")
+        .ok_or_else(|| SolveError::Missing("seeds".into()).into())
 }
 
-pub fn part_2(input: &Input) -> u64 {
-    let mut ranges: Vec<(u64, u64)> = vec![];
-    let mut next_ranges: Vec<(u64, u64)> = vec![];
-
+pub fn part_2(input: &Input) -> Result<u64, Error> {
+    input.check()?;
     input
-        .seeds
-        .iter()
-        .tuples()
-        .filter_map(|(&start, &len)| {
-            ranges.clear();
-            ranges.push((start, len));
-            next_ranges.clear();
-
-            input.maps.iter().for_each(|map| {
-                map.iter().for_each(|conv| {
-                    let mut i = 0;
-                    while let Some((start, len)) = ranges.get_mut(i) {
-                        i += 1;
-                        let conv_end = conv.source_start + conv.len;
-                        let end = *start + *len;
-
-                        if *start >= conv.source_start && conv_end > *start {
-                            //    [----  ]
-                            // [       ]
-                            //    [-]
-                            // [      ]
-                            next_ranges.push((
-                                conv.dest_start + *start - conv.source_start,
-                                (conv_end - *start).min(*len),
-                            ));
-
-                            *start = end.min(conv_end);
-                            *len = end.saturating_sub(*start);
-                        } else if *start < conv.source_start && conv.source_start < end {
-                            // [   ----]
-                            //     [
-                            next_ranges.push((conv.dest_start, end - conv.source_start));
-
-                            *len = conv.source_start - *start;
-                            if conv_end < end {
-                                ranges.push((conv.source_start, end - conv_end));
-                            }
-                        }
-                    }
-                });
-
-                next_ranges.extend(ranges.iter().filter(|(_, len)| *len != 0));
-                std::mem::swap(&mut ranges, &mut next_ranges);
-                next_ranges.clear();
-            });
-
-            ranges.iter().map(|(start, _)| *start).min()
-        })
-        .min()
-        .expect("no mappings")
+        .almanac()?
+        .min_over(&input.seed_ranges())
+        .ok_or_else(|| SolveError::Missing("seed ranges".into()).into())
+}
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Input = Input;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part_1(input: &Input) -> Result<Answer, Error> {
+        Ok(part_1(input)?.into())
+    }
+
+    fn part_2(input: &Input) -> Result<Answer, Error> {
+        Ok(part_2(input)?.into())
+    }
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use indoc::indoc;
 
+    pub(crate) const EXAMPLE: &str = indoc! {
+        "
+        seeds: 79 14 55 13
+
+        seed-to-soil map:
+        50 98 2
+        52 50 48
+
+        soil-to-fertilizer map:
+        0 15 37
+        37 52 2
+        39 0 15
+
+        fertilizer-to-water map:
+        49 53 8
+        0 11 42
+        42 0 7
+        57 7 4
+
+        water-to-light map:
+        88 18 7
+        18 25 70
+
+        light-to-temperature map:
+        45 77 23
+        81 45 19
+        68 64 13
+
+        temperature-to-humidity map:
+        0 69 1
+        1 0 69
+        humidity-to-location map:
+        60 56 37
+        56 93 4
+        "
+    };
+
     #[test]
     fn test() {
-        let input = input_generator(indoc! {
-            "
-            seeds: 79 14 55 13
-
-            seed-to-soil map:
-            50 98 2
-            52 50 48
-
-            soil-to-fertilizer map:
-            0 15 37
-            37 52 2
-            39 0 15
-
-            fertilizer-to-water map:
-            49 53 8
-            0 11 42
-            42 0 7
-            57 7 4
-
-            water-to-light map:
-            88 18 7
-            18 25 70
-
-            light-to-temperature map:
-            45 77 23
-            81 45 19
-            68 64 13
-
-            temperature-to-humidity map:
-            0 69 1
-            1 0 69
-            humidity-to-location map:
-            60 56 37
-            56 93 4
-            "
-        });
-        assert_eq!(part_1(&input), 35);
-        assert_eq!(part_2(&input), 46);
+        let input = input_generator(EXAMPLE).unwrap();
+        assert_eq!(part_1(&input), Ok(35));
+        assert_eq!(part_2(&input), Ok(46));
+
+        assert_eq!(
+            input.seeds_landing_in(&[82..83, 46..47]),
+            Ok(vec![79..80, 82..83])
+        );
+        assert_eq!(
+            input
+                .conversion("seed", "fertilizer")
+                .unwrap()
+                .preimage(&[53..54, 38..39]),
+            [14..15, 51..52]
+        );
+        assert!(input.seeds_landing_in(&[0..20, 20..46]).unwrap().is_empty());
+
+        // the lowest location reached from the seed ranges, found the slow way round
+        let lowest = (0..)
+            .find(|&location| {
+                !input
+                    .seeds_landing_in(std::slice::from_ref(&(location..location + 1)))
+                    .unwrap()
+                    .is_empty()
+            })
+            .unwrap();
+        assert_eq!(part_2(&input), Ok(lowest));
     }
 }
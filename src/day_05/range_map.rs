@@ -0,0 +1,242 @@
+use std::ops::Range;
+
+use super::Conversion;
+
+/// Where a piece of a [`RangeMap`] starts, and how far it moves the values in it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Piece {
+    start: u64,
+    offset: i128,
+}
+
+/// A piecewise function over every `u64`, which moves each piece by a fixed offset.
+/// The pieces are sorted and there are no gaps between them: values that no conversion covers map
+/// to themselves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeMap {
+    /// Sorted by start, the first starting at 0, with no two neighbours sharing an offset.
+    pieces: Vec<Piece>,
+}
+
+impl Default for RangeMap {
+    fn default() -> Self {
+        RangeMap::identity()
+    }
+}
+
+impl RangeMap {
+    pub fn identity() -> Self {
+        RangeMap {
+            pieces: vec![Piece {
+                start: 0,
+                offset: 0,
+            }],
+        }
+    }
+
+    /// The map made by `conversions`, where the first of any overlapping conversions wins, as
+    /// when they are looked up in order.
+    pub fn new(conversions: &[Conversion]) -> Self {
+        let source =
+            |c: &Conversion| c.source_start as u128..c.source_start as u128 + c.len as u128;
+        let mut starts = conversions
+            .iter()
+            .flat_map(|c| [source(c).start, source(c).end])
+            .chain([0])
+            .filter(|&start| start <= u64::MAX as u128)
+            .collect::<Vec<_>>();
+        starts.sort_unstable();
+        starts.dedup();
+
+        RangeMap::from_pieces(starts.into_iter().map(|start| {
+            Piece {
+                start: start as u64,
+                offset: conversions
+                    .iter()
+                    .find(|c| source(c).contains(&start))
+                    .map_or(0, |c| c.dest_start as i128 - c.source_start as i128),
+            }
+        }))
+    }
+
+    /// Builds a map from sorted pieces, merging neighbours that share an offset.
+    fn from_pieces(pieces: impl Iterator<Item = Piece>) -> Self {
+        let mut merged: Vec<Piece> = vec![];
+        for piece in pieces {
+            if merged.last().is_none_or(|last| last.offset != piece.offset) {
+                merged.push(piece);
+            }
+        }
+        RangeMap { pieces: merged }
+    }
+
+    /// The pieces as the values they cover, which may end at 2^64, and their offsets.
+    fn spans(&self) -> impl Iterator<Item = (Range<u128>, i128)> + '_ {
+        self.pieces.iter().enumerate().map(|(i, piece)| {
+            let end = self
+                .pieces
+                .get(i + 1)
+                .map_or(1 << 64, |next| next.start as u128);
+            (piece.start as u128..end, piece.offset)
+        })
+    }
+
+    fn offset(&self, value: u64) -> i128 {
+        let i = self.pieces.partition_point(|piece| piece.start <= value);
+        self.pieces[i - 1].offset
+    }
+
+    pub fn apply(&self, value: u64) -> u64 {
+        (value as i128 + self.offset(value)) as u64
+    }
+
+    /// The images of `ranges`, one range for every piece each of them overlaps. An image that
+    /// would end at 2^64 is cut short at `u64::MAX`.
+    pub fn apply_ranges(&self, ranges: &[Range<u64>]) -> Vec<Range<u64>> {
+        ranges
+            .iter()
+            .flat_map(|range| {
+                self.spans().filter_map(move |(span, offset)| {
+                    let start = span.start.max(range.start as u128);
+                    let end = span.end.min(range.end as u128);
+                    let image = to_range(start as i128 + offset..end as i128 + offset);
+                    (start < end && !image.is_empty()).then_some(image)
+                })
+            })
+            .collect()
+    }
+
+    /// The smallest value any of `ranges` maps to, if they aren't all empty.
+    pub fn min_over(&self, ranges: &[Range<u64>]) -> Option<u64> {
+        self.apply_ranges(ranges)
+            .iter()
+            .map(|range| range.start)
+            .min()
+    }
+
+    /// Every value that maps into one of `ranges`, as sorted ranges that don't touch. Values no
+    /// conversion covers are their own preimage, and a preimage that would end at 2^64 is cut
+    /// short at `u64::MAX`.
+    pub fn preimage(&self, ranges: &[Range<u64>]) -> Vec<Range<u64>> {
+        let mut preimage = ranges
+            .iter()
+            .flat_map(|range| {
+                self.spans().filter_map(move |(span, offset)| {
+                    let start = (span.start as i128 + offset).max(range.start as i128);
+                    let end = (span.end as i128 + offset).min(range.end as i128);
+                    let preimage = to_range(start - offset..end - offset);
+                    (start < end && !preimage.is_empty()).then_some(preimage)
+                })
+            })
+            .collect::<Vec<_>>();
+        preimage.sort_unstable_by_key(|range| (range.start, range.end));
+
+        let mut merged: Vec<Range<u64>> = vec![];
+        for range in preimage {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+        merged
+    }
+
+    /// Every value that maps to `value`.
+    pub fn preimages(&self, value: u64) -> Vec<u64> {
+        self.spans()
+            .filter_map(|(span, offset)| {
+                let source = value as i128 - offset;
+                (span.start as i128 <= source && source < span.end as i128).then_some(source as u64)
+            })
+            .collect()
+    }
+
+    /// The map that applies `self` and then `then`.
+    pub fn compose(&self, then: &RangeMap) -> RangeMap {
+        let mut starts = vec![];
+        for (span, offset) in self.spans() {
+            starts.push(span.start);
+            // where the values in this piece cross into a new piece of `then`
+            starts.extend(
+                then.pieces
+                    .iter()
+                    .map(|piece| piece.start as i128 - offset)
+                    .filter(|&start| span.start as i128 <= start && start < span.end as i128)
+                    .map(|start| start as u128),
+            );
+        }
+        starts.sort_unstable();
+        starts.dedup();
+
+        RangeMap::from_pieces(starts.into_iter().map(|start| {
+            let start = start as u64;
+            Piece {
+                start,
+                offset: self.offset(start) + then.offset(self.apply(start)),
+            }
+        }))
+    }
+}
+
+/// `values` as a range of `u64`s, cut short at `u64::MAX`.
+fn to_range(values: Range<i128>) -> Range<u64> {
+    let clamp = |value: i128| value.clamp(0, u64::MAX as i128) as u64;
+    clamp(values.start)..clamp(values.end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conversion(dest_start: u64, source_start: u64, len: u64) -> Conversion {
+        Conversion {
+            dest_start,
+            source_start,
+            len,
+        }
+    }
+
+    #[test]
+    fn test() {
+        let seed_to_soil = RangeMap::new(&[conversion(50, 98, 2), conversion(52, 50, 48)]);
+        assert_eq!(seed_to_soil.apply(79), 81);
+        assert_eq!(seed_to_soil.apply(99), 51);
+        assert_eq!(seed_to_soil.apply(100), 100);
+        assert_eq!(seed_to_soil.apply(u64::MAX), u64::MAX);
+        assert_eq!(
+            seed_to_soil.apply_ranges(&[45..100, 150..151]),
+            [45..50, 52..100, 50..52, 150..151]
+        );
+        assert_eq!(seed_to_soil.min_over(&[200..201, 60..120]), Some(50));
+        assert_eq!(seed_to_soil.min_over(&[60..60, 7..7]), None);
+
+        let first_wins = RangeMap::new(&[conversion(0, 10, 10), conversion(100, 15, 10)]);
+        assert_eq!(first_wins.apply(17), 7);
+        assert_eq!(first_wins.apply(22), 107);
+
+        let shift = RangeMap::new(&[conversion(0, 2, 3)]);
+        let composed = seed_to_soil.compose(&shift);
+        for value in (0..200).chain([u64::MAX - 1, u64::MAX]) {
+            assert_eq!(
+                composed.apply(value),
+                shift.apply(seed_to_soil.apply(value))
+            );
+        }
+        // destinations and sources that end at 2^64 are cut short at u64::MAX
+        let to_top = RangeMap::new(&[conversion(u64::MAX - 4, 10, 5)]);
+        assert_eq!(to_top.apply(14), u64::MAX);
+        assert_eq!(
+            to_top.apply_ranges(&[8..20, 30..31]),
+            [8..10, u64::MAX - 4..u64::MAX, 15..20, 30..31]
+        );
+        assert_eq!(to_top.min_over(&[14..15, 12..14]), Some(u64::MAX - 2));
+        let from_top = RangeMap::new(&[conversion(0, u64::MAX - 4, 5)]);
+        assert_eq!(
+            from_top.preimage(&[3..4, 4..5]),
+            [3..5, u64::MAX - 1..u64::MAX]
+        );
+
+        assert_eq!(RangeMap::identity().compose(&seed_to_soil), seed_to_soil);
+        assert_eq!(seed_to_soil.compose(&RangeMap::identity()), seed_to_soil);
+    }
+}
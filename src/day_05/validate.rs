@@ -0,0 +1,293 @@
+use std::fmt;
+
+use itertools::Itertools;
+
+use super::{Conversion, Input, Map};
+use crate::parse::{Location, ParseError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueKind {
+    /// The source range overlaps that of the conversion on another line, so which one applies
+    /// depends on their order.
+    OverlappingSources {
+        line: usize,
+    },
+    /// The destination range overlaps that of the conversion on another line, so some values have
+    /// more than one source.
+    CollidingDestinations {
+        line: usize,
+    },
+    ZeroLength,
+    /// The range ends past `u64::MAX`.
+    Overflow,
+    /// The seed range ends past `u64::MAX`, so [`Input::seed_ranges`] cuts it short there.
+    SeedRangeOverflow,
+    /// The map leads back to the source category of the map on another line.
+    Cycle {
+        line: usize,
+    },
+}
+
+/// Something wrong with one line of an almanac.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    /// The header of the map the line belongs to, or `seeds`.
+    pub header: String,
+    /// 1-based line number.
+    pub line: usize,
+    pub kind: IssueKind,
+}
+
+impl Issue {
+    /// Whether the answers can't be trusted because of it.
+    pub fn is_fatal(&self) -> bool {
+        matches!(
+            self.kind,
+            IssueKind::OverlappingSources { .. } | IssueKind::Overflow | IssueKind::Cycle { .. }
+        )
+    }
+
+    fn expected(&self) -> String {
+        let what = match self.kind {
+            IssueKind::OverlappingSources { line } => {
+                format!("a source range that doesn't overlap line {line}'s")
+            }
+            IssueKind::CollidingDestinations { line } => {
+                format!("a destination range that doesn't overlap line {line}'s")
+            }
+            IssueKind::ZeroLength => "a range that isn't empty".to_owned(),
+            IssueKind::Overflow => "a range that ends by 18446744073709551615".to_owned(),
+            IssueKind::SeedRangeOverflow => {
+                "a seed range that ends by 18446744073709551615".to_owned()
+            }
+            IssueKind::Cycle { line } => format!("a map that doesn't lead back to line {line}'s"),
+        };
+        format!("{what} in the {}", self.header)
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {} ({}): ", self.line, self.header)?;
+        match self.kind {
+            IssueKind::OverlappingSources { line } => {
+                write!(f, "source range overlaps line {line}'s")
+            }
+            IssueKind::CollidingDestinations { line } => {
+                write!(f, "destination range overlaps line {line}'s")
+            }
+            IssueKind::ZeroLength => write!(f, "range is empty"),
+            IssueKind::Overflow => write!(f, "range ends past u64::MAX"),
+            IssueKind::SeedRangeOverflow => write!(f, "seed range is cut short at u64::MAX"),
+            IssueKind::Cycle { line } => write!(f, "map leads back to line {line}'s"),
+        }
+    }
+}
+
+fn overlap(a: (u64, u64), b: (u64, u64)) -> bool {
+    a.0 < b.0.saturating_add(b.1) && b.0 < a.0.saturating_add(a.1)
+}
+
+impl Map {
+    pub fn validate(&self) -> Vec<Issue> {
+        let issue = |i: usize, kind| Issue {
+            header: self.header(),
+            line: self.line + 1 + i,
+            kind,
+        };
+        let mut issues = vec![];
+        for (i, c) in self.conversions.iter().enumerate() {
+            if c.len == 0 {
+                issues.push(issue(i, IssueKind::ZeroLength));
+                continue;
+            }
+            if c.source_start.checked_add(c.len).is_none()
+                || c.dest_start.checked_add(c.len).is_none()
+            {
+                issues.push(issue(i, IssueKind::Overflow));
+            }
+            for (j, earlier) in self.conversions[..i].iter().enumerate() {
+                let line = self.line + 1 + j;
+                if overlap((c.source_start, c.len), (earlier.source_start, earlier.len)) {
+                    issues.push(issue(i, IssueKind::OverlappingSources { line }));
+                }
+                if overlap((c.dest_start, c.len), (earlier.dest_start, earlier.len)) {
+                    issues.push(issue(i, IssueKind::CollidingDestinations { line }));
+                }
+            }
+        }
+        issues
+    }
+}
+
+impl Input {
+    /// Everything wrong with the seeds and each map, in the order of their lines, then the first
+    /// cycle of maps there is.
+    pub fn validate(&self) -> Vec<Issue> {
+        let seeds = self
+            .seeds
+            .iter()
+            .tuples()
+            .filter(|(&start, &len)| start.checked_add(len).is_none())
+            .map(|_| Issue {
+                header: "seeds".to_owned(),
+                line: 1,
+                kind: IssueKind::SeedRangeOverflow,
+            });
+        let cycle = self.cycle().map(|cycle| self.cycle_issue(&cycle));
+        seeds
+            .chain(self.maps.iter().flat_map(Map::validate))
+            .chain(cycle)
+            .collect()
+    }
+
+    fn cycle_issue(&self, cycle: &[usize]) -> Issue {
+        let last = &self.maps[cycle[cycle.len() - 1]];
+        Issue {
+            header: last.header(),
+            line: last.line,
+            kind: IssueKind::Cycle {
+                line: self.maps[cycle[0]].line,
+            },
+        }
+    }
+
+    /// Fails on the first issue that would make the answers wrong. Cycles only count if they go
+    /// through a category on the way from seeds to locations.
+    pub fn check(&self) -> Result<(), ParseError> {
+        let path = self.path("seed", "location").unwrap_or_default();
+        let categories = path
+            .iter()
+            .map(|&i| self.maps[i].source.as_str())
+            .collect::<Vec<_>>();
+        let cycle = self
+            .cycle_through(&categories)
+            .map(|cycle| self.cycle_issue(&cycle));
+        let mut issues = self
+            .validate()
+            .into_iter()
+            .filter(|issue| !matches!(issue.kind, IssueKind::Cycle { .. }))
+            .chain(cycle);
+        match issues.find(Issue::is_fatal) {
+            None => Ok(()),
+            Some(issue) => Err(ParseError {
+                location: Some(Location {
+                    line: issue.line,
+                    column: 1,
+                    source_line: self.source_line(issue.line),
+                }),
+                ..ParseError::expected(issue.expected())
+            }),
+        }
+    }
+
+    /// The text of line `line`, as far as it can be told from what was parsed.
+    fn source_line(&self, line: usize) -> String {
+        let conversion = |c: &Conversion| format!("{} {} {}", c.dest_start, c.source_start, c.len);
+        if line == 1 {
+            return format!("seeds: {}", self.seeds.iter().join(" "));
+        }
+        self.maps
+            .iter()
+            .find_map(|map| match line.checked_sub(map.line)? {
+                0 => Some(format!("{}:", map.header())),
+                i => map.conversions.get(i - 1).map(conversion),
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day_05::{input_generator, part_1, part_2};
+    use crate::solution::Error;
+    use indoc::indoc;
+
+    #[test]
+    fn test() {
+        let input = input_generator(indoc! {"
+            seeds: 79 14 18446744073709551610 10
+
+            seed-to-soil map:
+            50 98 2
+            52 50 48
+            60 90 10
+            1 2 0
+
+            soil-to-fertilizer map:
+            0 18446744073709551600 100
+        "})
+        .unwrap();
+
+        assert_eq!(
+            input.validate(),
+            [
+                (1, "seeds", IssueKind::SeedRangeOverflow),
+                (
+                    6,
+                    "seed-to-soil map",
+                    IssueKind::OverlappingSources { line: 4 }
+                ),
+                (
+                    6,
+                    "seed-to-soil map",
+                    IssueKind::OverlappingSources { line: 5 }
+                ),
+                (
+                    6,
+                    "seed-to-soil map",
+                    IssueKind::CollidingDestinations { line: 5 }
+                ),
+                (7, "seed-to-soil map", IssueKind::ZeroLength),
+                (10, "soil-to-fertilizer map", IssueKind::Overflow),
+            ]
+            .map(|(line, header, kind)| Issue {
+                header: header.to_owned(),
+                line,
+                kind
+            })
+        );
+        assert_eq!(
+            input.validate()[1].to_string(),
+            "line 6 (seed-to-soil map): source range overlaps line 4's"
+        );
+
+        assert_eq!(
+            input.validate()[0].to_string(),
+            "line 1 (seeds): seed range is cut short at u64::MAX"
+        );
+        let Err(Error::Parse(err)) = part_1(&input) else {
+            panic!("an overlap should be reported at its position");
+        };
+        assert_eq!(err.location.unwrap().line, 6);
+
+        // seed ranges past u64::MAX are cut short rather than refused
+        let input = input_generator(indoc! {"
+            seeds: 18446744073709551610 10
+
+            seed-to-location map:
+            0 0 5
+        "})
+        .unwrap();
+        assert_eq!(part_1(&input), Ok(10));
+        assert_eq!(part_2(&input), Ok(18446744073709551610));
+
+        let input = input_generator(indoc! {"
+            seeds: 79 14
+
+            seed-to-soil map:
+            50 98 2
+            60 90 10
+        "})
+        .unwrap();
+        let Err(Error::Parse(err)) = part_2(&input) else {
+            panic!("an overlap should be reported at its position");
+        };
+        assert_eq!(
+            err.expected,
+            "a source range that doesn't overlap line 4's in the seed-to-soil map"
+        );
+        assert_eq!(err.location.unwrap().source_line, "60 90 10");
+    }
+}
@@ -1,11 +1,15 @@
 use nom::{
-    bytes::complete::tag,
     character::complete::{line_ending, space1, u64},
     multi::separated_list1,
     sequence::tuple,
     Parser,
 };
 
+use crate::{
+    parse::{finish, tag, IResult, ParseError},
+    solution::{Answer, Error, Solution},
+};
+
 pub struct Input {
     times: Vec<u64>,
     best_distances: Vec<u64>,
@@ -14,7 +18,7 @@ pub struct Input {
 const START_SPEED_MM_PER_MS: u64 = 0;
 const ACCEL_MM_PER_MS_2: u64 = 1;
 
-fn parse_input(input: &str) -> nom::IResult<&str, Input> {
+fn parse_input(input: &str) -> IResult<'_, Input> {
     tuple((
         tag("Time:"),
         space1,
@@ -31,10 +35,8 @@ fn parse_input(input: &str) -> nom::IResult<&str, Input> {
     .parse(input)
 }
 
-pub fn input_generator(input: &str) -> Input {
-    let (remaining, result) = parse_input(input).expect("failed to parse input");
-    assert!(remaining.trim().is_empty(), "failed to parse entire input");
-    result
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    finish(input, parse_input)
 }
 
 pub fn part_1(input: &Input) -> u64 {
@@ -77,19 +79,39 @@ fn merge_nums(nums: &[u64]) -> u64 {
         .fold(0, |sum, next| sum * 10_u64.pow(1 + next.ilog10()) + *next)
 }
 
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input = Input;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part_1(input: &Input) -> Result<Answer, Error> {
+        Ok(part_1(input).into())
+    }
+
+    fn part_2(input: &Input) -> Result<Answer, Error> {
+        Ok(part_2(input).into())
+    }
+}
+
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use indoc::indoc;
 
+    pub(crate) const EXAMPLE: &str = indoc! {
+        "
+        Time:      7  15   30
+        Distance:  9  40  200
+        "
+    };
+
     #[test]
     fn test() {
-        let input = input_generator(indoc! {
-            "
-            Time:      7  15   30
-            Distance:  9  40  200
-            "
-        });
+        let input = input_generator(EXAMPLE).unwrap();
         assert_eq!(part_1(&input), 288);
         assert_eq!(part_2(&input), 71503);
     }
@@ -2,12 +2,18 @@ use std::cmp::Ordering;
 
 use itertools::Itertools;
 use nom::{
-    character::complete::{anychar, line_ending, space1, u32},
+    character::complete::{line_ending, one_of, space1, u32},
+    error::context,
     multi::{count, separated_list0},
     sequence::tuple,
     Parser,
 };
 
+use crate::{
+    parse::{finish, IResult, ParseError},
+    solution::{Answer, Error, Solution},
+};
+
 pub struct Input {
     hands: Vec<(Hand, u32)>,
 }
@@ -16,11 +22,11 @@ pub struct Hand {
     cards: [u8; 5],
 }
 
-fn parse_input(input: &str) -> nom::IResult<&str, Input> {
+fn parse_input(input: &str) -> IResult<'_, Input> {
     let (input, hands) = separated_list0(
         line_ending,
         tuple((
-            count(anychar, 5).map(|nums| Hand {
+            count(context("a card", one_of("AKQJT98765432")), 5).map(|nums| Hand {
                 cards: nums
                     .iter()
                     .map(|c| *c as u8)
@@ -36,10 +42,8 @@ fn parse_input(input: &str) -> nom::IResult<&str, Input> {
     Ok((input, Input { hands }))
 }
 
-pub fn input_generator(input: &str) -> Input {
-    let (remaining, result) = parse_input(input).expect("failed to parse input");
-    assert!(remaining.trim().is_empty(), "failed to parse entire input");
-    result
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    finish(input, parse_input)
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -267,22 +271,42 @@ pub fn part_2(input: &Input) -> u32 {
         .sum()
 }
 
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Input = Input;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part_1(input: &Input) -> Result<Answer, Error> {
+        Ok(part_1(input).into())
+    }
+
+    fn part_2(input: &Input) -> Result<Answer, Error> {
+        Ok(part_2(input).into())
+    }
+}
+
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use indoc::indoc;
 
+    pub(crate) const EXAMPLE: &str = indoc! {
+        "
+        32T3K 765
+        T55J5 684
+        KK677 28
+        KTJJT 220
+        QQQJA 483
+        "
+    };
+
     #[test]
     fn test() {
-        let input = input_generator(indoc! {
-            "
-            32T3K 765
-            T55J5 684
-            KK677 28
-            KTJJT 220
-            QQQJA 483
-            "
-        });
+        let input = input_generator(EXAMPLE).unwrap();
         assert_eq!(part_1(&input), 6440);
         assert_eq!(part_2(&input), 5905);
     }
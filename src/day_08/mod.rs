@@ -1,13 +1,18 @@
 use nom::{
-    bytes::complete::tag,
-    character::complete::{alphanumeric1, line_ending, multispace1},
-    combinator::fail,
+    branch::alt,
+    character::complete::{alphanumeric1, char, line_ending, multispace1},
+    error::context,
     multi::{many1, separated_list0},
     sequence::tuple,
     Parser,
 };
 use rustc_hash::FxHashMap;
 
+use crate::{
+    parse::{finish, tag, IResult, ParseError},
+    solution::{Answer, Error, SolveError, Solution},
+};
+
 pub struct Input {
     init: Option<Id>,
     goal: Option<Id>,
@@ -24,15 +29,17 @@ pub enum Direction {
 
 type Id = u16;
 
-fn parse_dir(input: &str) -> nom::IResult<&str, Direction> {
-    match input.chars().next() {
-        Some('L') => Ok((&input[1..], Direction::Left)),
-        Some('R') => Ok((&input[1..], Direction::Right)),
-        _ => fail(input),
-    }
+fn parse_dir(input: &str) -> IResult<'_, Direction> {
+    context(
+        "a direction",
+        alt((
+            char('L').map(|_| Direction::Left),
+            char('R').map(|_| Direction::Right),
+        )),
+    )(input)
 }
 
-fn parse_input(input: &str) -> nom::IResult<&str, Input> {
+fn parse_input(input: &str) -> IResult<'_, Input> {
     let (input, instructions) = many1(parse_dir)(input)?;
     let (input, _) = multispace1(input)?;
 
@@ -88,15 +95,17 @@ fn parse_input(input: &str) -> nom::IResult<&str, Input> {
     ))
 }
 
-pub fn input_generator(input: &str) -> Input {
-    let (remaining, result) = parse_input(input).expect("failed to parse input");
-    assert!(remaining.trim().is_empty(), "failed to parse entire input");
-    result
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    finish(input, parse_input)
 }
 
-pub fn part_1(input: &Input) -> u64 {
-    let mut id = input.init.expect("starting element not found");
-    let goal = input.goal.expect("goal element not found");
+pub fn part_1(input: &Input) -> Result<u64, SolveError> {
+    let mut id = input
+        .init
+        .ok_or(SolveError::Missing("element named AAA".into()))?;
+    let goal = input
+        .goal
+        .ok_or(SolveError::Missing("element named ZZZ".into()))?;
     let mut steps = 0;
     while id != goal {
         let (left, right) = input.elements[id as usize];
@@ -107,7 +116,7 @@ pub fn part_1(input: &Input) -> u64 {
         steps += 1;
     }
 
-    steps as u64
+    Ok(steps as u64)
 }
 
 #[derive(Debug)]
@@ -118,7 +127,7 @@ struct Cycle {
     end_offsets: Vec<usize>,
 }
 
-pub fn part_2(input: &Input) -> u64 {
+pub fn part_2(input: &Input) -> Result<u64, SolveError> {
     let mut cycles = vec![];
     for (id, _) in input.starting_mask.iter().enumerate().filter(|(_, b)| **b) {
         let mut ends = vec![];
@@ -159,7 +168,11 @@ pub fn part_2(input: &Input) -> u64 {
     // stupid lcm answer is somehow right for my input, but it doesn't generalise
     // cycles.iter().map(|c| c.start_offset + c.end_offsets[0]).reduce(num::integer::lcm).unwrap() as u64
 
-    let starting_offset = cycles.iter().map(|cycle| cycle.start_offset).max().unwrap();
+    let starting_offset = cycles
+        .iter()
+        .map(|cycle| cycle.start_offset)
+        .max()
+        .ok_or(SolveError::Missing("element ending in A".into()))?;
     for cycle in &mut cycles {
         let cycle_starting_offset = starting_offset - cycle.start_offset;
         let offset_in_cycle = cycle_starting_offset % cycle.period;
@@ -193,30 +206,50 @@ pub fn part_2(input: &Input) -> u64 {
                 }
             });
 
-    (starting_offset + full_cycle.1[0]) as u64
+    Ok((starting_offset + full_cycle.1[0]) as u64)
+}
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Input = Input;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part_1(input: &Input) -> Result<Answer, Error> {
+        Ok(part_1(input)?.into())
+    }
+
+    fn part_2(input: &Input) -> Result<Answer, Error> {
+        Ok(part_2(input)?.into())
+    }
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use indoc::indoc;
 
+    pub(crate) const EXAMPLE: &str = indoc! {
+        "
+        RL
+
+        AAA = (BBB, CCC)
+        BBB = (DDD, EEE)
+        CCC = (ZZZ, GGG)
+        DDD = (DDD, DDD)
+        EEE = (EEE, EEE)
+        GGG = (GGG, GGG)
+        ZZZ = (ZZZ, ZZZ)
+        "
+    };
+
     #[test]
     fn test() {
-        let input = input_generator(indoc! {
-            "
-            RL
-
-            AAA = (BBB, CCC)
-            BBB = (DDD, EEE)
-            CCC = (ZZZ, GGG)
-            DDD = (DDD, DDD)
-            EEE = (EEE, EEE)
-            GGG = (GGG, GGG)
-            ZZZ = (ZZZ, ZZZ)
-            "
-        });
-        assert_eq!(part_1(&input), 2);
+        let input = input_generator(EXAMPLE).unwrap();
+        assert_eq!(part_1(&input), Ok(2));
         let input2 = input_generator(indoc! {
             "
             LLR
@@ -225,8 +258,9 @@ mod tests {
             BBB = (AAA, ZZZ)
             ZZZ = (ZZZ, ZZZ)
             "
-        });
-        assert_eq!(part_1(&input2), 6);
+        })
+        .unwrap();
+        assert_eq!(part_1(&input2), Ok(6));
 
         let input3 = input_generator(indoc! {
             "
@@ -241,13 +275,8 @@ mod tests {
             22Z = (22B, 22B)
             XXX = (XXX, XXX)
             "
-        });
-        assert_eq!(part_2(&input3), 6);
-    }
-
-    #[test]
-    fn test_my_input() {
-        let input = input_generator(include_str!("../../input/2023/day8.txt"));
-        assert_eq!(part_2(&input), 16342438708751);
+        })
+        .unwrap();
+        assert_eq!(part_2(&input3), Ok(6));
     }
 }
@@ -2,26 +2,31 @@ use itertools::Itertools;
 use nom::{
     character::complete::{i64, space1},
     combinator::opt,
+    multi::many1,
     sequence::preceded,
 };
 
+use crate::{
+    parse::{parse_lines, IResult, ParseError},
+    solution::{Answer, Error, Solution},
+};
+
 pub struct Input {
     solutions: (i64, i64),
 }
 
-fn parse_input(input: &str) -> nom::IResult<&str, Input> {
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let rows = parse_lines(input, input, many1(parse_int))?;
+
     let mut row = vec![];
     let mut next_row = vec![];
     let mut heads = vec![];
 
-    let solutions = input
-        .lines()
-        .map(|mut line| {
+    let solutions = rows
+        .into_iter()
+        .map(|nums| {
             row.clear();
-            while let Ok((next_line, num)) = parse_int(line) {
-                line = next_line;
-                row.push(num);
-            }
+            row.extend(nums);
 
             let mut tail_sum = 0;
             heads.clear();
@@ -45,19 +50,13 @@ fn parse_input(input: &str) -> nom::IResult<&str, Input> {
         })
         .fold((0, 0), |acc, next| (acc.0 + next.0, acc.1 + next.1));
 
-    Ok(("", Input { solutions }))
+    Ok(Input { solutions })
 }
 
-fn parse_int(input: &str) -> nom::IResult<&str, i64> {
+fn parse_int(input: &str) -> IResult<'_, i64> {
     preceded(opt(space1), i64)(input)
 }
 
-pub fn input_generator(input: &str) -> Input {
-    let (remaining, result) = parse_input(input).expect("failed to parse input");
-    assert!(remaining.trim().is_empty(), "failed to parse entire input");
-    result
-}
-
 pub fn part_1(input: &Input) -> i64 {
     input.solutions.1
 }
@@ -66,20 +65,40 @@ pub fn part_2(input: &Input) -> i64 {
     input.solutions.0
 }
 
+pub struct Day09;
+
+impl Solution for Day09 {
+    type Input = Input;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part_1(input: &Input) -> Result<Answer, Error> {
+        Ok(part_1(input).into())
+    }
+
+    fn part_2(input: &Input) -> Result<Answer, Error> {
+        Ok(part_2(input).into())
+    }
+}
+
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use indoc::indoc;
 
+    pub(crate) const EXAMPLE: &str = indoc! {
+        "
+        0 3 6 9 12 15
+        1 3 6 10 15 21
+        10 13 16 21 30 45
+        "
+    };
+
     #[test]
     fn test() {
-        let input = input_generator(indoc! {
-            "
-            0 3 6 9 12 15
-            1 3 6 10 15 21
-            10 13 16 21 30 45
-            "
-        });
+        let input = input_generator(EXAMPLE).unwrap();
         assert_eq!(part_1(&input), 114);
         assert_eq!(part_2(&input), 2);
     }
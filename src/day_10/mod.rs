@@ -1,63 +1,40 @@
-use num::Integer;
+use crate::{
+    grid::{Grid, Pos},
+    parse::ParseError,
+    solution::{Answer, Error, SolveError, Solution},
+};
 
 pub struct Input {
-    data: Vec<u8>,
-    width: usize,
-    height: usize,
+    pipes: Grid<u8>,
 }
 
-fn parse_input(input: &str) -> nom::IResult<&str, Input> {
-    let width = input.bytes().position(|b| b == b'\n').unwrap() + 1;
-    let height = input.len() / width;
-
-    Ok((
-        "",
-        Input {
-            data: input.as_bytes().to_owned(),
-            width,
-            height,
-        },
-    ))
-}
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let pipes = Grid::parse(
+        input,
+        // anything that isn't a pipe or 'S' is ground, as in the marked-up examples
+        |c| c.is_ascii().then_some(c as u8),
+        "a pipe or ground",
+    )?;
+    if pipes.position(|b| *b == b'S').is_none() {
+        return Err(ParseError::expected("a starting position 'S'"));
+    }
 
-pub fn input_generator(input: &str) -> Input {
-    let (remaining, result) = parse_input(input).expect("failed to parse input");
-    assert!(remaining.trim().is_empty(), "failed to parse entire input");
-    result
+    Ok(Input { pipes })
 }
 
-fn find_cycle(input: &Input) -> (u8, Vec<usize>) {
-    let start_pos = input
-        .data
-        .iter()
+fn find_cycle(input: &Input) -> Result<(u8, Vec<Pos>), SolveError> {
+    let pipes = &input.pipes;
+    let starting_pos = pipes
         .position(|b| *b == b'S')
-        .expect("starting position not found");
-    let starting_pos = (start_pos / input.width, start_pos % input.width);
-
-    let mut first_moves = [None; 4];
-
-    const STEP: (usize, usize) = (1, 1);
-    if starting_pos.1 + STEP.1 < input.width {
-        first_moves[0] = Some((starting_pos.0, starting_pos.1 + STEP.1));
-    }
-    if starting_pos.1 >= STEP.1 {
-        first_moves[1] = Some((starting_pos.0, starting_pos.1 - STEP.1));
-    }
-    if starting_pos.0 + STEP.0 < input.height {
-        first_moves[2] = Some((starting_pos.0 + STEP.0, starting_pos.1));
-    }
-    if starting_pos.0 >= STEP.0 {
-        first_moves[3] = Some((starting_pos.0 - STEP.0, starting_pos.1));
-    }
+        .ok_or(SolveError::Missing("starting position 'S'".into()))?;
 
-    'outer: for first_move in first_moves.into_iter().flatten() {
+    'outer: for first_move in pipes.neighbours_4(starting_pos) {
         let mut pos = first_move;
         let mut last_pos = starting_pos;
-        let mut cycle = vec![starting_pos.0 * input.width + starting_pos.1];
+        let mut cycle = vec![starting_pos];
         while pos != starting_pos {
-            let idx = pos.0 * input.width + pos.1;
-            cycle.push(idx);
-            let diffs = match input.data[idx] {
+            cycle.push(pos);
+            let diffs = match pipes[pos] {
                 b'|' => [(-1, 0), (1, 0)],
                 b'-' => [(0, -1), (0, 1)],
                 b'L' => [(-1, 0), (0, 1)],
@@ -67,23 +44,7 @@ fn find_cycle(input: &Input) -> (u8, Vec<usize>) {
                 _ => continue 'outer,
             };
 
-            let next_positions = &diffs.map(|diff| {
-                let new_pos = (diff.0 + pos.0 as isize, diff.1 + pos.1 as isize);
-                if new_pos.0 >= 0
-                    && new_pos.0 < input.height as isize
-                    && new_pos.1 >= 0
-                    && new_pos.1 < input.width as isize
-                {
-                    Some((new_pos.0 as usize, new_pos.1 as usize))
-                } else {
-                    None
-                }
-            });
-            let next_pos = next_positions
-                .iter()
-                .flatten()
-                .find(|next_pos| last_pos != **next_pos);
-
+            let next_positions = diffs.map(|diff| pipes.offset(pos, diff));
             if !next_positions
                 .iter()
                 .flatten()
@@ -92,54 +53,55 @@ fn find_cycle(input: &Input) -> (u8, Vec<usize>) {
                 continue 'outer;
             }
 
-            if let Some(next_pos) = next_pos {
-                (last_pos, pos) = (pos, *next_pos);
-            } else {
+            let Some(next_pos) = next_positions
+                .into_iter()
+                .flatten()
+                .find(|next_pos| last_pos != *next_pos)
+            else {
                 continue 'outer;
-            }
+            };
+            (last_pos, pos) = (pos, next_pos);
         }
 
         // identify shape of 'S' pipe
-        let first = (cycle[0] as isize).div_rem(&(input.width as isize));
-        let second = (cycle[1] as isize).div_rem(&(input.width as isize));
-        let last = (*cycle.last().unwrap() as isize).div_rem(&(input.width as isize));
-        let mut diffs = [
-            (second.0 - first.0, second.1 - first.1),
-            (last.0 - first.0, last.1 - first.1),
-        ];
+        let diff = |pos: Pos| {
+            (
+                pos.0 as isize - starting_pos.0 as isize,
+                pos.1 as isize - starting_pos.1 as isize,
+            )
+        };
+        let mut diffs = [diff(cycle[1]), diff(cycle[cycle.len() - 1])];
         diffs.sort();
 
         let pipe = match diffs {
-            [(1, 0), (1, 0)] => b'|',
-            [(0, 1), (0, 1)] => b'-',
+            [(-1, 0), (1, 0)] => b'|',
+            [(0, -1), (0, 1)] => b'-',
             [(-1, 0), (0, 1)] => b'L',
             [(-1, 0), (0, -1)] => b'J',
             [(0, -1), (1, 0)] => b'7',
             [(0, 1), (1, 0)] => b'F',
-            _ => panic!("failed to identify shape of S piece"),
+            _ => return Err(SolveError::Missing("pipe shape that fits in place of 'S'".into())),
         };
 
-        return (pipe, cycle);
+        return Ok((pipe, cycle));
     }
 
-    panic!("no solution found");
+    Err(SolveError::Missing("loop of pipes through 'S'".into()))
 }
 
-pub fn part_1(input: &Input) -> usize {
-    (find_cycle(input).1.len() + 1) / 2
+pub fn part_1(input: &Input) -> Result<usize, SolveError> {
+    Ok(find_cycle(input)?.1.len().div_ceil(2))
 }
 
-pub fn part_2(input: &Input) -> usize {
-    let (starting_pipe, cycle) = find_cycle(input);
-    let mut pipes = input.data.clone();
+pub fn part_2(input: &Input) -> Result<usize, SolveError> {
+    let (starting_pipe, cycle) = find_cycle(input)?;
+    let mut pipes = input.pipes.clone();
     pipes[cycle[0]] = starting_pipe;
 
-    let cycle_table = cycle
-        .iter()
-        .fold(vec![false; input.data.len()], |mut acc, idx| {
-            acc[*idx] = true;
-            acc
-        });
+    let mut in_cycle = pipes.map(|_| false);
+    for pos in &cycle {
+        in_cycle[*pos] = true;
+    }
 
     /*
        The idea here is to scan the grid left-to-right up-to-down and keep track of whether we are inside the loop.
@@ -151,47 +113,64 @@ pub fn part_2(input: &Input) -> usize {
     let mut count_inside = 0;
     let mut inside = false;
     let mut start_pipe = 0u8;
-    for j in 0..input.height {
-        for i in 0..input.width {
-            let idx = j * input.width + i;
-            if !cycle_table[idx] {
-                if inside {
-                    count_inside += 1;
-                }
-            } else {
-                match pipes[idx] {
-                    b'|' => inside = !inside,
-                    b'-' => {}
-                    pipe @ (b'L' | b'F') => start_pipe = pipe,
-                    b'J' if start_pipe == b'F' => inside = !inside,
-                    b'7' if start_pipe == b'L' => inside = !inside,
-                    _ => start_pipe = 0,
-                }
+    for (pos, pipe) in pipes.iter() {
+        if !in_cycle[pos] {
+            if inside {
+                count_inside += 1;
+            }
+        } else {
+            match pipe {
+                b'|' => inside = !inside,
+                b'-' => {}
+                pipe @ (b'L' | b'F') => start_pipe = *pipe,
+                b'J' if start_pipe == b'F' => inside = !inside,
+                b'7' if start_pipe == b'L' => inside = !inside,
+                _ => start_pipe = 0,
             }
         }
     }
 
-    count_inside
+    Ok(count_inside)
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Input;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part_1(input: &Input) -> Result<Answer, Error> {
+        Ok(part_1(input)?.into())
+    }
+
+    fn part_2(input: &Input) -> Result<Answer, Error> {
+        Ok(part_2(input)?.into())
+    }
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use indoc::indoc;
 
+    pub(crate) const EXAMPLE: &str = indoc! {
+        "
+        .....
+        .S-7.
+        .|.|.
+        .L-J.
+        .....
+        "
+    };
+
     #[test]
     fn test() {
-        let input = input_generator(indoc! {
-            "
-            .....
-            .S-7.
-            .|.|.
-            .L-J.
-            .....
-            "
-        });
-        assert_eq!(part_1(&input), 4);
-        assert_eq!(part_2(&input), 1);
+        let input = input_generator(EXAMPLE).unwrap();
+        assert_eq!(part_1(&input), Ok(4));
+        assert_eq!(part_2(&input), Ok(1));
 
         let input = input_generator(indoc! {
             "
@@ -201,9 +180,10 @@ mod tests {
             |F--J
             LJ.LJ
             "
-        });
-        assert_eq!(part_1(&input), 8);
-        assert_eq!(part_2(&input), 1);
+        })
+        .unwrap();
+        assert_eq!(part_1(&input), Ok(8));
+        assert_eq!(part_2(&input), Ok(1));
 
         let input = input_generator(indoc! {
             "
@@ -217,8 +197,9 @@ mod tests {
             .L--J.L--J.
             ...........
             "
-        });
-        assert_eq!(part_2(&input), 4);
+        })
+        .unwrap();
+        assert_eq!(part_2(&input), Ok(4));
 
         let input = input_generator(indoc! {
             "
@@ -233,8 +214,9 @@ mod tests {
             ....FJL-7.||.||||...
             ....L---J.LJ.LJLJ...
             "
-        });
-        assert_eq!(part_2(&input), 8);
+        })
+        .unwrap();
+        assert_eq!(part_2(&input), Ok(8));
 
         let input = input_generator(indoc! {
             "
@@ -249,8 +231,9 @@ mod tests {
             L.L7LFJ|||||FJL7||LJ
             L7JLJL-JLJLJL--JLJ.L
            "
-        });
-        assert_eq!(part_2(&input), 10);
+        })
+        .unwrap();
+        assert_eq!(part_2(&input), Ok(10));
 
         let input = input_generator(indoc! {
             "
@@ -262,14 +245,8 @@ mod tests {
             .L7|||.
             ..LJLJ.
             "
-        });
-        assert_eq!(part_2(&input), 1);
-    }
-
-    #[test]
-    fn test_my_input() {
-        let input = input_generator(include_str!("../../input/2023/day10.txt"));
-        assert_eq!(part_1(&input), 6942);
-        assert_eq!(part_2(&input), 297);
+        })
+        .unwrap();
+        assert_eq!(part_2(&input), Ok(1));
     }
 }
@@ -1,58 +1,50 @@
 use itertools::Itertools;
 
+use crate::{
+    grid::Grid,
+    parse::ParseError,
+    solution::{Answer, Error, Solution},
+};
+
 pub struct Input {
-    points: Vec<(u64, u64)>,
+    galaxies: Grid<bool>,
 }
 
-fn parse_input(input: &str) -> nom::IResult<&str, Input> {
-    let points = input
-        .lines()
-        .enumerate()
-        .fold(vec![], |mut points, (row, line)| {
-            points.extend(
-                line.bytes()
-                    .enumerate()
-                    .filter_map(|(col, b)| (b == b'#').then_some((row as u64, col as u64))),
-            );
-            points
-        });
-
-    Ok(("", Input { points }))
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    let galaxies = Grid::parse(
+        input,
+        |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        },
+        "'.' or '#'",
+    )?;
+
+    Ok(Input { galaxies })
 }
 
-pub fn input_generator(input: &str) -> Input {
-    let (remaining, result) = parse_input(input).expect("failed to parse input");
-    assert!(remaining.trim().is_empty(), "failed to parse entire input");
-    result
+/// Where each row or column ends up once every empty one has grown by `growth`.
+fn expanded(empty: impl Iterator<Item = bool>, growth: u64) -> Vec<u64> {
+    empty
+        .scan(0, |expansion, empty| {
+            let coord = *expansion;
+            *expansion += if empty { 1 + growth } else { 1 };
+            Some(coord)
+        })
+        .collect()
 }
 
 fn solve(input: &Input, growth: u64) -> u64 {
-    let mut points = input.points.clone();
-
-    {
-        let mut expansion = 0;
-        let mut last_0 = 0;
-        for p in &mut points {
-            if p.0 != last_0 {
-                expansion += (p.0 - last_0 - 1) * growth;
-                last_0 = p.0;
-            }
-            p.0 += expansion;
-        }
-    }
+    let galaxies = &input.galaxies;
+    let rows = expanded(galaxies.rows().map(|row| !row.contains(&true)), growth);
+    let cols = expanded(galaxies.columns().map(|mut col| !col.any(|g| *g)), growth);
 
-    points.sort_by_key(|p| p.1);
-    {
-        let mut expansion = 0;
-        let mut last_1 = 0;
-        for p in &mut points {
-            if p.1 != last_1 {
-                expansion += (p.1 - last_1 - 1) * growth;
-                last_1 = p.1;
-            }
-            p.1 += expansion;
-        }
-    }
+    let points = galaxies
+        .iter()
+        .filter(|(_, galaxy)| **galaxy)
+        .map(|((row, col), _)| (rows[row], cols[col]))
+        .collect_vec();
 
     let mut sum = 0;
     for (p1, p2) in points.iter().tuple_combinations() {
@@ -70,35 +62,48 @@ pub fn part_2(input: &Input) -> u64 {
     solve(input, 1_000_000 - 1)
 }
 
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Input;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part_1(input: &Input) -> Result<Answer, Error> {
+        Ok(part_1(input).into())
+    }
+
+    fn part_2(input: &Input) -> Result<Answer, Error> {
+        Ok(part_2(input).into())
+    }
+}
+
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use indoc::indoc;
 
+    pub(crate) const EXAMPLE: &str = indoc! {
+        "
+        ...#......
+        .......#..
+        #.........
+        ..........
+        ......#...
+        .#........
+        .........#
+        ..........
+        .......#..
+        #...#.....
+        "
+    };
+
     #[test]
     fn test() {
-        let input = input_generator(indoc! {
-            "
-            ...#......
-            .......#..
-            #.........
-            ..........
-            ......#...
-            .#........
-            .........#
-            ..........
-            .......#..
-            #...#.....
-            "
-        });
+        let input = input_generator(EXAMPLE).unwrap();
         assert_eq!(part_1(&input), 374);
         assert_eq!(part_2(&input), 82000210);
     }
-
-    #[test]
-    fn test_my_input() {
-        let input = input_generator(include_str!("../../input/2023/day11.txt"));
-        assert_eq!(part_1(&input), 9693756);
-        assert_eq!(part_2(&input), 717878258016);
-    }
 }
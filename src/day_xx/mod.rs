@@ -1,16 +1,20 @@
 use nom::{
-    bytes::complete::*,
     character::complete::*,
     multi::*,
     sequence::tuple,
     Parser,
 };
 
+use crate::{
+    parse::{finish, tag, IResult, ParseError},
+    solution::{Answer, Error, Solution},
+};
+
 pub struct Input {
 
 }
 
-fn parse_input(input: &str) -> nom::IResult<&str, Input> {
+fn parse_input(input: &str) -> IResult<'_, Input> {
     Ok((
         input,
         Input {
@@ -18,10 +22,8 @@ fn parse_input(input: &str) -> nom::IResult<&str, Input> {
     ))
 }
 
-pub fn input_generator(input: &str) -> Input {
-    let (remaining, result) = parse_input(input).expect("failed to parse input");
-    assert!(remaining.trim().is_empty(), "failed to parse entire input");
-    result
+pub fn input_generator(input: &str) -> Result<Input, ParseError> {
+    finish(input, parse_input)
 }
 
 pub fn part_1(input: &Input) -> u32 {
@@ -32,25 +34,38 @@ pub fn part_2(input: &Input) -> u32 {
     0
 }
 
+pub struct Day__DAY_PADDED__;
+
+impl Solution for Day__DAY_PADDED__ {
+    type Input = Input;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part_1(input: &Input) -> Result<Answer, Error> {
+        Ok(part_1(input).into())
+    }
+
+    fn part_2(input: &Input) -> Result<Answer, Error> {
+        Ok(part_2(input).into())
+    }
+}
+
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use indoc::indoc;
 
+    pub(crate) const EXAMPLE: &str = indoc! {
+        "
+        "
+    };
+
     #[test]
     fn test() {
-        let input = input_generator(indoc! {
-            "
-            "
-        });
+        let input = input_generator(EXAMPLE).unwrap();
         assert_eq!(part_1(&input), );
         // assert_eq!(part_2(&input),);
     }
-
-    #[test]
-    fn test_my_input() {
-        let input = input_generator(include_str!("../../input/2023/dayxx.txt"));
-        // assert_eq!(part_1(&input), );
-        // assert_eq!(part_2(&input),);
-    }
 }
@@ -0,0 +1,267 @@
+use std::{
+    borrow::Cow,
+    fmt,
+    io::{self, Read},
+    path::PathBuf,
+};
+
+/// Environment variable that overrides the directory puzzle inputs are read from.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+pub const DEFAULT_INPUT_DIR: &str = "input/2023";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// Embedded inputs with the `embed-input` feature, otherwise the input directory.
+    Default,
+    Dir(PathBuf),
+    Path(PathBuf),
+    Stdin,
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    /// There is no input for the day; callers should skip it rather than fail.
+    Missing(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Missing(location) => write!(f, "input not found at {location}"),
+            InputError::Io(e) => write!(f, "failed to read input: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+impl From<io::Error> for InputError {
+    fn from(e: io::Error) -> Self {
+        InputError::Io(e)
+    }
+}
+
+pub trait InputProvider {
+    fn input(&self, day: u8) -> Result<String, InputError>;
+}
+
+/// Reads `day{N}.txt` from a directory.
+pub struct DirProvider {
+    dir: PathBuf,
+}
+
+impl DirProvider {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        DirProvider { dir: dir.into() }
+    }
+
+    /// Uses the directory named by [`INPUT_DIR_VAR`], falling back to [`DEFAULT_INPUT_DIR`].
+    pub fn from_env() -> Self {
+        Self::new(std::env::var_os(INPUT_DIR_VAR).unwrap_or_else(|| DEFAULT_INPUT_DIR.into()))
+    }
+
+    pub fn path(&self, day: u8) -> PathBuf {
+        self.dir.join(format!("day{day}.txt"))
+    }
+}
+
+impl InputProvider for DirProvider {
+    fn input(&self, day: u8) -> Result<String, InputError> {
+        let path = self.path(day);
+        std::fs::read_to_string(&path).map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => InputError::Missing(path.display().to_string()),
+            _ => InputError::Io(e),
+        })
+    }
+}
+
+/// Reads a single file regardless of the day.
+pub struct FileProvider {
+    path: PathBuf,
+}
+
+impl FileProvider {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FileProvider { path: path.into() }
+    }
+}
+
+impl InputProvider for FileProvider {
+    fn input(&self, _day: u8) -> Result<String, InputError> {
+        Ok(std::fs::read_to_string(&self.path)?)
+    }
+}
+
+pub struct StdinProvider;
+
+impl InputProvider for StdinProvider {
+    fn input(&self, _day: u8) -> Result<String, InputError> {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        Ok(input)
+    }
+}
+
+/// Inputs compiled into the binary from [`DEFAULT_INPUT_DIR`].
+#[cfg(feature = "embed-input")]
+pub struct EmbeddedProvider;
+
+#[cfg(feature = "embed-input")]
+impl InputProvider for EmbeddedProvider {
+    fn input(&self, day: u8) -> Result<String, InputError> {
+        crate::registry::embedded_input(day)
+            .map(str::to_owned)
+            .ok_or_else(|| InputError::Missing(format!("embedded inputs (day {day})")))
+    }
+}
+
+pub fn provider(source: &InputSource) -> Box<dyn InputProvider> {
+    match source {
+        #[cfg(feature = "embed-input")]
+        InputSource::Default => Box::new(EmbeddedProvider),
+        #[cfg(not(feature = "embed-input"))]
+        InputSource::Default => Box::new(DirProvider::from_env()),
+        InputSource::Dir(dir) => Box::new(DirProvider::new(dir)),
+        InputSource::Path(path) => Box::new(FileProvider::new(path)),
+        InputSource::Stdin => Box::new(StdinProvider),
+    }
+}
+
+/// Puts an input into the shape every input generator expects, whatever editor or download saved it:
+/// no UTF-8 byte order mark, `\n` line endings, no trailing whitespace on any line, and exactly one
+/// newline at the end unless the input is empty.
+pub fn normalize(input: &str) -> Cow<'_, str> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let mut normalized = String::with_capacity(input.len() + 1);
+    for line in input.lines() {
+        normalized.push_str(line.trim_end());
+        normalized.push('\n');
+    }
+    normalized.truncate(normalized.trim_end().len());
+    if !normalized.is_empty() {
+        normalized.push('\n');
+    }
+
+    if normalized == input {
+        Cow::Borrowed(input)
+    } else {
+        Cow::Owned(normalized)
+    }
+}
+
+/// The ways `input` might have been saved, which should all be read the same way.
+#[cfg(test)]
+fn line_ending_variants(input: &str) -> Vec<(&'static str, String)> {
+    let crlf = input.replace('\n', "\r\n");
+    vec![
+        ("lf", input.to_owned()),
+        ("crlf", crlf.clone()),
+        ("no trailing newline", input.trim_end().to_owned()),
+        ("extra trailing newlines", format!("{input}\n\n")),
+        ("bom", format!("\u{feff}{input}")),
+        ("trailing whitespace", input.replace('\n', " \t\n")),
+        (
+            "everything",
+            format!("\u{feff}{}  \r\n\r\n", crlf.trim_end()),
+        ),
+    ]
+}
+
+/// Loads the personal input for a day, or reports that it is being skipped when it isn't available.
+#[cfg(test)]
+pub(crate) fn personal_input(day: u8) -> Option<String> {
+    match provider(&InputSource::Default).input(day) {
+        Ok(input) => Some(input),
+        Err(e) => {
+            eprintln!("skipping day {day}: {e}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{registry::DAYS, solution::Part, *};
+
+    /// Each day's example inputs, and the parts to solve from them.
+    const EXAMPLES: &[(u8, &str, &[Part])] = &[
+        (1, day_01::tests::EXAMPLE_1, &Part::ALL),
+        (1, day_01::tests::EXAMPLE_2, &Part::ALL),
+        (2, day_02::tests::EXAMPLE, &Part::ALL),
+        (3, day_03::tests::EXAMPLE, &Part::ALL),
+        (4, day_04::tests::EXAMPLE, &Part::ALL),
+        (5, day_05::tests::EXAMPLE, &Part::ALL),
+        (6, day_06::tests::EXAMPLE, &Part::ALL),
+        (7, day_07::tests::EXAMPLE, &Part::ALL),
+        (8, day_08::tests::EXAMPLE, &Part::ALL),
+        (9, day_09::tests::EXAMPLE, &Part::ALL),
+        (10, day_10::tests::EXAMPLE, &Part::ALL),
+        (11, day_11::tests::EXAMPLE, &Part::ALL),
+        // part 2 doesn't finish on the example in reasonable time, which is also why
+        // `day_12::tests::test` is ignored
+        (12, day_12::tests::EXAMPLE, &[Part::One]),
+    ];
+
+    #[test]
+    fn test() {
+        let dir = std::env::temp_dir().join(format!("aoc-input-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("day3.txt"), "467..114..\n").unwrap();
+
+        let provider = DirProvider::new(&dir);
+        assert_eq!(provider.input(3).unwrap(), "467..114..\n");
+        assert!(matches!(provider.input(4), Err(InputError::Missing(_))));
+
+        let file = FileProvider::new(dir.join("day3.txt"));
+        assert_eq!(file.input(25).unwrap(), "467..114..\n");
+        assert!(matches!(
+            FileProvider::new(dir.join("day4.txt")).input(4),
+            Err(InputError::Io(_))
+        ));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_line_endings() {
+        for day in DAYS {
+            assert!(
+                EXAMPLES.iter().any(|(number, ..)| *number == day.number),
+                "day {} has no example in EXAMPLES",
+                day.number
+            );
+        }
+
+        for &(number, example, parts) in EXAMPLES {
+            let day = registry::find(number).unwrap();
+            let solve = |input: &str| {
+                let parsed = day.parse(input).unwrap();
+                parts
+                    .iter()
+                    .map(|part| parsed.part(*part))
+                    .collect::<Vec<_>>()
+            };
+
+            let expected = solve(example);
+            for (name, variant) in line_ending_variants(example) {
+                assert_eq!(
+                    solve(&variant),
+                    expected,
+                    "day {number}, {name} line endings"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert!(matches!(normalize("1 2\n3 4\n"), Cow::Borrowed(_)));
+        assert_eq!(normalize(""), "");
+        assert_eq!(normalize("\n \n"), "");
+        for (name, variant) in line_ending_variants("1 2\n\n3 4\n") {
+            assert_eq!(normalize(&variant), "1 2\n\n3 4\n", "{name}");
+        }
+    }
+}
@@ -1,3 +1,17 @@
+pub mod answers;
+pub mod bench;
+pub mod bitset;
+pub mod cli;
+pub mod grid;
+pub mod input;
+pub mod parse;
+pub mod registry;
+pub mod report;
+pub mod rng;
+pub mod runner;
+pub mod scaffold;
+pub mod solution;
+
 pub mod day_01;
 pub mod day_02;
 pub mod day_03;
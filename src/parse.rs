@@ -0,0 +1,290 @@
+use std::{borrow::Cow, fmt};
+
+use nom::{
+    error::{ContextError, ErrorKind, FromExternalError},
+    Parser,
+};
+
+/// Where in the input a [`ParseError`] occurred.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column, counted in characters.
+    pub column: usize,
+    pub source_line: String,
+}
+
+/// An input that couldn't be parsed or solved, with what was expected and where.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: Option<u8>,
+    /// `None` for problems with the input as a whole, such as a missing starting point.
+    pub location: Option<Location>,
+    pub expected: Cow<'static, str>,
+}
+
+impl ParseError {
+    pub fn expected(expected: impl Into<Cow<'static, str>>) -> Self {
+        ParseError {
+            day: None,
+            location: None,
+            expected: expected.into(),
+        }
+    }
+
+    /// An error at byte `offset` of `input`.
+    pub fn at(input: &str, offset: usize, expected: impl Into<Cow<'static, str>>) -> Self {
+        let offset = offset.min(input.len());
+        let line_start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+
+        ParseError {
+            location: Some(Location {
+                line: input[..line_start].matches('\n').count() + 1,
+                column: input[line_start..offset].chars().count() + 1,
+                source_line: input[line_start..line_end]
+                    .trim_end_matches('\r')
+                    .to_owned(),
+            }),
+            ..Self::expected(expected)
+        }
+    }
+
+    /// An error at the start of `rest`, which must be a slice of `input`.
+    pub fn at_slice(input: &str, rest: &str, expected: impl Into<Cow<'static, str>>) -> Self {
+        Self::at(input, offset_of(input, rest), expected)
+    }
+
+    pub fn in_day(self, day: u8) -> Self {
+        ParseError {
+            day: Some(day),
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "day {day}: ")?;
+        }
+        let Some(location) = &self.location else {
+            return write!(f, "expected {}", self.expected);
+        };
+
+        let gutter = " ".repeat(location.line.to_string().len());
+        writeln!(
+            f,
+            "line {}, column {}: expected {}",
+            location.line, location.column, self.expected
+        )?;
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{} | {}", location.line, location.source_line)?;
+        write!(f, "{gutter} | {}^", " ".repeat(location.column - 1))
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn offset_of(input: &str, rest: &str) -> usize {
+    let offset = (rest.as_ptr() as usize).wrapping_sub(input.as_ptr() as usize);
+    debug_assert!(offset <= input.len(), "slice is not part of the input");
+    offset.min(input.len())
+}
+
+/// A nom error that remembers what was expected, for turning into a [`ParseError`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NomError<'a> {
+    pub input: &'a str,
+    pub expected: Cow<'static, str>,
+}
+
+pub type IResult<'a, T> = nom::IResult<&'a str, T, NomError<'a>>;
+
+fn describe(kind: ErrorKind) -> Cow<'static, str> {
+    match kind {
+        ErrorKind::Digit => "a number".into(),
+        ErrorKind::Char => "a character".into(),
+        ErrorKind::Space => "a space".into(),
+        ErrorKind::MultiSpace => "whitespace".into(),
+        ErrorKind::CrLf => "a line ending".into(),
+        ErrorKind::Alpha => "a letter".into(),
+        ErrorKind::AlphaNumeric => "a letter or digit".into(),
+        ErrorKind::OneOf => "one of the allowed characters".into(),
+        ErrorKind::Eof => "end of input".into(),
+        kind => kind.description().to_lowercase().into(),
+    }
+}
+
+impl<'a> nom::error::ParseError<&'a str> for NomError<'a> {
+    fn from_error_kind(input: &'a str, kind: ErrorKind) -> Self {
+        NomError {
+            input,
+            expected: describe(kind),
+        }
+    }
+
+    fn append(_input: &'a str, _kind: ErrorKind, other: Self) -> Self {
+        other
+    }
+
+    fn from_char(input: &'a str, c: char) -> Self {
+        NomError {
+            input,
+            expected: format!("{c:?}").into(),
+        }
+    }
+
+    fn or(self, other: Self) -> Self {
+        // keep whichever alternative got furthest, and list both if they failed at the same place
+        match self.input.len().cmp(&other.input.len()) {
+            std::cmp::Ordering::Less => self,
+            std::cmp::Ordering::Greater => other,
+            std::cmp::Ordering::Equal => NomError {
+                expected: format!("{} or {}", self.expected, other.expected).into(),
+                ..other
+            },
+        }
+    }
+}
+
+impl<'a> ContextError<&'a str> for NomError<'a> {
+    fn add_context(input: &'a str, ctx: &'static str, other: Self) -> Self {
+        // only describe the failure by its context if nothing inside the context matched
+        if input.len() == other.input.len() {
+            NomError {
+                input,
+                expected: ctx.into(),
+            }
+        } else {
+            other
+        }
+    }
+}
+
+impl<'a, E> FromExternalError<&'a str, E> for NomError<'a> {
+    fn from_external_error(input: &'a str, kind: ErrorKind, _e: E) -> Self {
+        nom::error::ParseError::from_error_kind(input, kind)
+    }
+}
+
+/// Like [`nom::bytes::complete::tag`], but reports the missing tag when it fails.
+pub fn tag<'a>(t: &'static str) -> impl FnMut(&'a str) -> IResult<'a, &'a str> {
+    move |input: &'a str| match input.strip_prefix(t) {
+        Some(rest) => Ok((rest, &input[..t.len()])),
+        None => Err(nom::Err::Error(NomError {
+            input,
+            expected: format!("{t:?}").into(),
+        })),
+    }
+}
+
+fn to_parse_error(input: &str, e: nom::Err<NomError>) -> ParseError {
+    match e {
+        nom::Err::Error(e) | nom::Err::Failure(e) => {
+            ParseError::at_slice(input, e.input, e.expected)
+        }
+        nom::Err::Incomplete(_) => ParseError::at(input, input.len(), "more input"),
+    }
+}
+
+/// Runs a parser over all of `input`, allowing only trailing whitespace to be left over.
+pub fn finish<'a, T>(
+    input: &'a str,
+    mut parser: impl Parser<&'a str, T, NomError<'a>>,
+) -> Result<T, ParseError> {
+    match parser.parse(input) {
+        Ok((rest, out)) if rest.trim().is_empty() => Ok(out),
+        Ok((rest, _)) => Err(ParseError::at_slice(
+            input,
+            rest.trim_start(),
+            "end of input",
+        )),
+        Err(e) => Err(to_parse_error(input, e)),
+    }
+}
+
+/// Parses each line of `section` (a slice of `input`) entirely with `parser`.
+/// Blank lines are only allowed at the end.
+pub fn parse_lines<'a, T>(
+    input: &'a str,
+    section: &'a str,
+    mut parser: impl Parser<&'a str, T, NomError<'a>>,
+) -> Result<Vec<T>, ParseError> {
+    section
+        .trim_end()
+        .lines()
+        .map(|line| match parser.parse(line) {
+            Ok((rest, out)) if rest.trim_end().is_empty() => Ok(out),
+            Ok((rest, _)) => Err(ParseError::at_slice(input, rest, "end of line")),
+            Err(e) => Err(to_parse_error(input, e)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use nom::{
+        branch::alt,
+        character::complete::{char, u32},
+        error::context,
+        sequence::separated_pair,
+    };
+
+    #[test]
+    fn test() {
+        let input = "Game 1: 3 blue\nGame 2: 4 purple\n";
+        let err = parse_lines(
+            input,
+            input,
+            separated_pair(
+                tag("Game ").and(u32),
+                tag(": "),
+                separated_pair(
+                    u32,
+                    char(' '),
+                    context("a colour", alt((tag("red"), tag("blue")))),
+                ),
+            ),
+        )
+        .unwrap_err()
+        .in_day(2);
+
+        assert_eq!(
+            err.location,
+            Some(Location {
+                line: 2,
+                column: 11,
+                source_line: "Game 2: 4 purple".to_owned()
+            })
+        );
+        assert_eq!(
+            err.to_string(),
+            indoc! {"
+                day 2: line 2, column 11: expected a colour
+                  |
+                2 | Game 2: 4 purple
+                  |           ^"
+            }
+        );
+
+        let err = finish(input, tag("Game 1")).unwrap_err();
+        assert_eq!(err.location.unwrap().column, 7);
+        assert_eq!(err.expected, "end of input");
+
+        let err = finish("Card 1", alt((tag("Game"), tag("Round")))).unwrap_err();
+        assert_eq!(err.expected, "\"Game\" or \"Round\"");
+
+        assert_eq!(
+            ParseError::expected("a starting position 'S'")
+                .in_day(10)
+                .to_string(),
+            "day 10: expected a starting position 'S'"
+        );
+    }
+}
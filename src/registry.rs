@@ -0,0 +1,47 @@
+use crate::solution::Day;
+use crate::*;
+
+macro_rules! days {
+    ($($n:literal => $m:ident::$s:ident,)*) => {
+        pub static DAYS: &[Day] = &[$(Day::new::<$m::$s>($n),)*];
+
+        #[cfg(feature = "embed-input")]
+        pub(crate) fn embedded_input(day: u8) -> Option<&'static str> {
+            match day {
+                $($n => Some(include_str!(concat!("../input/2023/day", $n, ".txt"))),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+days! {
+    1 => day_01::Day01,
+    2 => day_02::Day02,
+    3 => day_03::Day03,
+    4 => day_04::Day04,
+    5 => day_05::Day05,
+    6 => day_06::Day06,
+    7 => day_07::Day07,
+    8 => day_08::Day08,
+    9 => day_09::Day09,
+    10 => day_10::Day10,
+    11 => day_11::Day11,
+    12 => day_12::Day12,
+}
+
+pub fn find(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_days_are_ordered() {
+        assert!(DAYS.windows(2).all(|w| w[0].number < w[1].number));
+        assert_eq!(find(5).map(|d| d.number), Some(5));
+        assert!(find(0).is_none());
+    }
+}
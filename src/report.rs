@@ -0,0 +1,263 @@
+use std::{
+    fmt,
+    io::{self, Write},
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::runner::Record;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("unknown format '{s}', expected text, json or csv")),
+        }
+    }
+}
+
+const CSV_HEADER: &str = "day,part,answer,type,parse_time_ns,solve_time_ns,status,detail";
+
+/// Writes records as they are produced, so that long runs report progress in every format.
+pub struct Reporter<W: Write> {
+    out: W,
+    format: Format,
+    written: usize,
+}
+
+impl<W: Write> Reporter<W> {
+    pub fn new(mut out: W, format: Format) -> io::Result<Self> {
+        match format {
+            Format::Text => {}
+            Format::Json => write!(out, "[")?,
+            Format::Csv => writeln!(out, "{CSV_HEADER}")?,
+        }
+        Ok(Reporter {
+            out,
+            format,
+            written: 0,
+        })
+    }
+
+    pub fn record(&mut self, record: &Record) -> io::Result<()> {
+        match self.format {
+            Format::Text => write_text(&mut self.out, record)?,
+            Format::Json => {
+                if self.written > 0 {
+                    write!(self.out, ",")?;
+                }
+                write!(self.out, "\n  ")?;
+                write_json(&mut self.out, record)?;
+            }
+            Format::Csv => write_csv(&mut self.out, record)?,
+        }
+        self.written += 1;
+        self.out.flush()
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.format == Format::Json {
+            writeln!(self.out, "{}]", if self.written > 0 { "\n" } else { "" })?;
+        }
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+fn write_text(out: &mut impl Write, record: &Record) -> io::Result<()> {
+    write!(out, "day {}-{}: ", record.day, record.part.number())?;
+    if let Some(answer) = &record.answer {
+        write!(out, "{answer} ")?;
+    }
+    write!(out, "{}", record.status.name())?;
+    // multi-line details, such as parse errors pointing into the input, go on the following lines
+    let detail = record.status.detail();
+    let (inline, block) = match &detail {
+        Some(detail) if detail.trim_end().contains('\n') => (None, Some(detail.trim_end())),
+        detail => (detail.as_deref(), None),
+    };
+    if let Some(detail) = inline {
+        write!(out, " ({detail})")?;
+    }
+    match (record.parse_time, record.solve_time) {
+        (Some(parse), Some(solve)) => writeln!(out, " [parse {parse:.2?}, solve {solve:.2?}]")?,
+        (Some(parse), None) => writeln!(out, " [parse {parse:.2?}]")?,
+        _ => writeln!(out)?,
+    }
+    for line in block.into_iter().flat_map(str::lines) {
+        writeln!(out, "    {line}")?;
+    }
+    Ok(())
+}
+
+struct JsonStr<'a>(&'a str);
+
+impl fmt::Display for JsonStr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("\"")?;
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+                c => write!(f, "{c}")?,
+            }
+        }
+        f.write_str("\"")
+    }
+}
+
+fn nanos(duration: Option<Duration>) -> Option<u128> {
+    duration.map(|d| d.as_nanos())
+}
+
+fn write_json(out: &mut impl Write, record: &Record) -> io::Result<()> {
+    fn or_null(value: Option<impl fmt::Display>) -> String {
+        value.map_or_else(|| "null".to_owned(), |v| v.to_string())
+    }
+
+    write!(
+        out,
+        "{{\"day\": {}, \"part\": {}, \"answer\": {}, \"type\": {}, \"parse_time_ns\": {}, \
+        \"solve_time_ns\": {}, \"status\": {}, \"detail\": {}}}",
+        record.day,
+        record.part.number(),
+        or_null(record.answer),
+        or_null(record.answer.map(|a| JsonStr(a.type_name()))),
+        or_null(nanos(record.parse_time)),
+        or_null(nanos(record.solve_time)),
+        JsonStr(record.status.name()),
+        or_null(record.status.detail().as_deref().map(JsonStr)),
+    )
+}
+
+struct CsvField<'a>(&'a str);
+
+impl fmt::Display for CsvField<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.contains([',', '"', '\n', '\r']) {
+            write!(f, "\"{}\"", self.0.replace('"', "\"\""))
+        } else {
+            f.write_str(self.0)
+        }
+    }
+}
+
+fn write_csv(out: &mut impl Write, record: &Record) -> io::Result<()> {
+    fn or_empty(value: Option<impl fmt::Display>) -> String {
+        value.map_or_else(String::new, |v| v.to_string())
+    }
+
+    writeln!(
+        out,
+        "{},{},{},{},{},{},{},{}",
+        record.day,
+        record.part.number(),
+        or_empty(record.answer),
+        or_empty(record.answer.map(|a| a.type_name())),
+        or_empty(nanos(record.parse_time)),
+        or_empty(nanos(record.solve_time)),
+        record.status.name(),
+        CsvField(&record.status.detail().unwrap_or_default()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        runner::Status,
+        solution::{Answer, Part},
+    };
+    use indoc::indoc;
+
+    fn report(format: Format, records: &[Record]) -> String {
+        let mut reporter = Reporter::new(vec![], format).unwrap();
+        for record in records {
+            reporter.record(record).unwrap();
+        }
+        String::from_utf8(reporter.finish().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test() {
+        let records = [
+            Record {
+                day: 9,
+                part: Part::Two,
+                answer: Some(Answer::I64(-2)),
+                parse_time: Some(Duration::from_micros(15)),
+                solve_time: Some(Duration::from_nanos(250)),
+                status: Status::Fail {
+                    expected: "2".to_owned(),
+                },
+            },
+            Record {
+                day: 10,
+                part: Part::One,
+                answer: None,
+                parse_time: None,
+                solve_time: None,
+                status: Status::Error("bad \"pipe\", at 3\n".to_owned()),
+            },
+            Record {
+                day: 2,
+                part: Part::One,
+                answer: None,
+                parse_time: Some(Duration::from_micros(3)),
+                solve_time: None,
+                status: Status::Error("day 2: expected a colour\n  |\n".to_owned()),
+            },
+        ];
+
+        assert_eq!(
+            report(Format::Text, &records),
+            indoc! {"
+                day 9-2: -2 fail (expected 2) [parse 15.00µs, solve 250.00ns]
+                day 10-1: error (bad \"pipe\", at 3
+                )
+                day 2-1: error [parse 3.00µs]
+                    day 2: expected a colour
+                      |
+            "}
+        );
+        assert_eq!(
+            report(Format::Json, &records),
+            indoc! {r#"
+                [
+                  {"day": 9, "part": 2, "answer": -2, "type": "i64", "parse_time_ns": 15000, "solve_time_ns": 250, "status": "fail", "detail": "expected 2"},
+                  {"day": 10, "part": 1, "answer": null, "type": null, "parse_time_ns": null, "solve_time_ns": null, "status": "error", "detail": "bad \"pipe\", at 3\n"},
+                  {"day": 2, "part": 1, "answer": null, "type": null, "parse_time_ns": 3000, "solve_time_ns": null, "status": "error", "detail": "day 2: expected a colour\n  |\n"}
+                ]
+            "#}
+        );
+        assert_eq!(report(Format::Json, &[]), "[]\n");
+        assert_eq!(
+            report(Format::Csv, &records),
+            indoc! {r#"
+                day,part,answer,type,parse_time_ns,solve_time_ns,status,detail
+                9,2,-2,i64,15000,250,fail,expected 2
+                10,1,,,,,error,"bad ""pipe"", at 3
+                "
+                2,1,,,3000,,error,"day 2: expected a colour
+                  |
+                "
+            "#}
+        );
+    }
+}
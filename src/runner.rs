@@ -0,0 +1,177 @@
+use std::{
+    panic::{self, AssertUnwindSafe},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    input::{InputError, InputProvider},
+    solution::{Answer, Day, Part},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    /// Solved, without an expected answer to compare against.
+    Solved,
+    Pass,
+    Fail {
+        expected: String,
+    },
+    Unknown,
+    /// Had no expected answer, and the computed one was written to the answers file.
+    Recorded,
+    Skipped(String),
+    Error(String),
+}
+
+impl Status {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Status::Solved => "ok",
+            Status::Pass => "pass",
+            Status::Fail { .. } => "fail",
+            Status::Unknown => "unknown",
+            Status::Recorded => "recorded",
+            Status::Skipped(_) => "skipped",
+            Status::Error(_) => "error",
+        }
+    }
+
+    pub fn detail(&self) -> Option<String> {
+        match self {
+            Status::Fail { expected } => Some(format!("expected {expected}")),
+            Status::Skipped(reason) | Status::Error(reason) => Some(reason.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Status::Fail { .. } | Status::Error(_))
+    }
+}
+
+/// The outcome of running one part of one day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub day: u8,
+    pub part: Part,
+    pub answer: Option<Answer>,
+    /// Time taken by the day's input generator, shared by both parts of the day.
+    pub parse_time: Option<Duration>,
+    pub solve_time: Option<Duration>,
+    pub status: Status,
+}
+
+impl Record {
+    fn unsolved(day: u8, part: Part, parse_time: Option<Duration>, status: Status) -> Record {
+        Record {
+            day,
+            part,
+            answer: None,
+            parse_time,
+            solve_time: None,
+            status,
+        }
+    }
+}
+
+/// Runs `f`, turning a panic into its message.
+pub fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "panicked".to_owned())
+    })
+}
+
+/// Loads a day's input, or the status every part of the day should be reported with if it can't be.
+pub fn load_input(day: &Day, provider: &dyn InputProvider) -> Result<String, Status> {
+    provider.input(day.number).map_err(|e| match e {
+        InputError::Missing(_) => Status::Skipped(e.to_string()),
+        InputError::Io(_) => Status::Error(e.to_string()),
+    })
+}
+
+/// Solves the given parts of a day, producing one record per part.
+pub fn run_day(day: &Day, provider: &dyn InputProvider, parts: &[Part]) -> Vec<Record> {
+    let unsolved = |parse_time, status: Status| {
+        parts
+            .iter()
+            .map(|&part| Record::unsolved(day.number, part, parse_time, status.clone()))
+            .collect()
+    };
+
+    let input = match load_input(day, provider) {
+        Ok(input) => input,
+        Err(status) => return unsolved(None, status),
+    };
+
+    let instant = Instant::now();
+    let parsed = match catch_panic(|| day.parse(&input)) {
+        Ok(Ok(parsed)) => parsed,
+        Ok(Err(e)) => return unsolved(Some(instant.elapsed()), Status::Error(e.to_string())),
+        Err(e) => return unsolved(Some(instant.elapsed()), Status::Error(e)),
+    };
+    let parse_time = Some(instant.elapsed());
+
+    parts
+        .iter()
+        .map(|&part| {
+            let instant = Instant::now();
+            match catch_panic(|| parsed.part(part)) {
+                Ok(Ok(answer)) => Record {
+                    day: day.number,
+                    part,
+                    answer: Some(answer),
+                    parse_time,
+                    solve_time: Some(instant.elapsed()),
+                    status: Status::Solved,
+                },
+                Ok(Err(e)) => {
+                    Record::unsolved(day.number, part, parse_time, Status::Error(e.to_string()))
+                }
+                Err(e) => Record::unsolved(day.number, part, parse_time, Status::Error(e)),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{input::FileProvider, registry::find};
+
+    #[test]
+    fn test() {
+        let missing = FileProvider::new("/nonexistent/day1.txt");
+        let records = run_day(find(1).unwrap(), &missing, &Part::ALL);
+        assert_eq!(records.len(), 2);
+        assert!(records
+            .iter()
+            .all(|r| r.status.is_failure() && r.answer.is_none()));
+
+        let path = std::env::temp_dir().join(format!("aoc-runner-test-{}.txt", std::process::id()));
+        std::fs::write(&path, "1abc2\nno digits\n").unwrap();
+        let records = run_day(find(1).unwrap(), &FileProvider::new(&path), &[Part::Two]);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].part, Part::Two);
+        assert!(records[0].parse_time.is_some());
+        let Status::Error(detail) = &records[0].status else {
+            panic!("expected an error, got {:?}", records[0].status);
+        };
+        assert!(detail.starts_with("day 1: line 2, column 1: expected a line containing a digit"));
+
+        std::fs::write(&path, "L\n\nBBB = (BBB, BBB)\n").unwrap();
+        let records = run_day(find(8).unwrap(), &FileProvider::new(&path), &[Part::One]);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(records[0].parse_time.is_some());
+        assert_eq!(
+            records[0].status,
+            Status::Error("day 8: the input has no element named AAA".to_owned())
+        );
+    }
+}
@@ -0,0 +1,391 @@
+use std::{
+    fmt, fs, io,
+    ops::Range,
+    path::{Path, PathBuf},
+    process,
+};
+
+use crate::input::DEFAULT_INPUT_DIR;
+
+/// The module every new day starts from, with `__DAY_PADDED__` standing for the zero-padded day and
+/// `__DAY__` for the day.
+/// Its test module holds the stubs for the example input and its expected answers. The example is
+/// also added to the line ending checks in `input`'s tests.
+pub const TEMPLATE: &str = include_str!("day_xx/mod.rs");
+
+/// File holding the `session=...` cookie used to download puzzle inputs.
+pub const DEFAULT_COOKIE_PATH: &str = "cookies";
+
+#[derive(Debug)]
+pub enum ScaffoldError {
+    /// The day already has a module or is already registered.
+    Exists(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for ScaffoldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScaffoldError::Exists(what) => write!(f, "{what} already exists"),
+            ScaffoldError::Io(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for ScaffoldError {}
+
+impl From<io::Error> for ScaffoldError {
+    fn from(e: io::Error) -> Self {
+        ScaffoldError::Io(e)
+    }
+}
+
+/// Somewhere to download a day's puzzle input from.
+pub trait InputFetcher {
+    fn fetch(&self, day: u8) -> io::Result<String>;
+}
+
+/// Downloads inputs from the Advent of Code website with `curl`, authenticated by a session cookie file.
+pub struct CurlFetcher {
+    cookie_file: PathBuf,
+}
+
+impl CurlFetcher {
+    pub fn new(cookie_file: impl Into<PathBuf>) -> Self {
+        CurlFetcher {
+            cookie_file: cookie_file.into(),
+        }
+    }
+}
+
+impl InputFetcher for CurlFetcher {
+    fn fetch(&self, day: u8) -> io::Result<String> {
+        let cookie = fs::read_to_string(&self.cookie_file).map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!("failed to read {}: {e}", self.cookie_file.display()),
+            )
+        })?;
+        let output = process::Command::new("curl")
+            .args([
+                "--fail",
+                "--silent",
+                "--show-error",
+                "--cookie",
+                cookie.trim(),
+            ])
+            .arg(format!("https://adventofcode.com/2023/day/{day}/input"))
+            .output()?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(io::Error::other(format!("curl failed: {}", stderr.trim())));
+        }
+        String::from_utf8(output.stdout).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+/// Creates and registers new days in a checkout of this repository.
+pub struct Scaffold {
+    root: PathBuf,
+}
+
+impl Scaffold {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Scaffold { root: root.into() }
+    }
+
+    fn path(&self, relative: impl AsRef<Path>) -> PathBuf {
+        self.root.join(relative)
+    }
+
+    /// Creates the module for `day` from the template, registers it, and downloads its input if
+    /// there is a fetcher and no input yet. Returns the files created or changed.
+    /// Nothing is written if the day already exists or its input can't be downloaded.
+    pub fn new_day(
+        &self,
+        day: u8,
+        fetcher: Option<&dyn InputFetcher>,
+    ) -> Result<Vec<PathBuf>, ScaffoldError> {
+        let padded = format!("{day:02}");
+        let module_dir = self.path(format!("src/day_{padded}"));
+        if module_dir.exists() {
+            return Err(ScaffoldError::Exists(module_dir.display().to_string()));
+        }
+
+        let lib_path = self.path("src/lib.rs");
+        let lib = register_module(&fs::read_to_string(&lib_path)?, &padded)?;
+        let registry_path = self.path("src/registry.rs");
+        let registry = register_day(&fs::read_to_string(&registry_path)?, day, &padded)?;
+        let input_rs_path = self.path("src/input.rs");
+        let input_rs = register_example(&fs::read_to_string(&input_rs_path)?, day, &padded)?;
+
+        let input_path = self.path(format!("{DEFAULT_INPUT_DIR}/day{day}.txt"));
+        let input = match fetcher.filter(|_| !input_path.exists()) {
+            Some(fetcher) => Some(fetcher.fetch(day)?),
+            None => None,
+        };
+
+        let module_path = module_dir.join("mod.rs");
+        fs::create_dir_all(&module_dir)?;
+        fs::write(&module_path, render_template(day))?;
+        fs::write(&lib_path, lib)?;
+        fs::write(&registry_path, registry)?;
+        fs::write(&input_rs_path, input_rs)?;
+        let mut changed = vec![module_path, lib_path, registry_path, input_rs_path];
+
+        if let Some(input) = input {
+            fs::create_dir_all(self.path(DEFAULT_INPUT_DIR))?;
+            fs::write(&input_path, input)?;
+            changed.push(input_path);
+        }
+
+        Ok(changed)
+    }
+}
+
+pub fn render_template(day: u8) -> String {
+    TEMPLATE
+        .replace("__DAY_PADDED__", &format!("{day:02}"))
+        .replace("__DAY__", &day.to_string())
+}
+
+/// Inserts `item` among `lines[range]`, before the first line that sorts after it according to `key`
+/// (and any comments just above that line), and otherwise after the last line that has a key.
+fn insert_sorted<K: Ord>(
+    lines: &mut Vec<String>,
+    range: Range<usize>,
+    item: String,
+    key: impl Fn(&str) -> Option<K>,
+) -> Result<(), ScaffoldError> {
+    let new_key = key(&item);
+    let keyed = range
+        .clone()
+        .filter_map(|i| Some((i, key(&lines[i])?)))
+        .collect::<Vec<_>>();
+    if keyed.iter().any(|(_, k)| Some(k) == new_key.as_ref()) {
+        return Err(ScaffoldError::Exists(item.trim().to_owned()));
+    }
+
+    let at = match keyed.iter().find(|(_, k)| Some(k) > new_key.as_ref()) {
+        // keep comments with the line they come before
+        Some((i, _)) => (range.start..*i)
+            .rev()
+            .take_while(|&j| lines[j].trim_start().starts_with("//"))
+            .last()
+            .unwrap_or(*i),
+        None => keyed.last().map_or(range.end, |(i, _)| i + 1),
+    };
+    lines.insert(at, item);
+    Ok(())
+}
+
+fn to_lines(source: &str) -> Vec<String> {
+    source.lines().map(str::to_owned).collect()
+}
+
+fn register_module(lib: &str, padded: &str) -> Result<String, ScaffoldError> {
+    let mut lines = to_lines(lib);
+    let len = lines.len();
+    insert_sorted(
+        &mut lines,
+        0..len,
+        format!("pub mod day_{padded};"),
+        |line| {
+            line.strip_prefix("pub mod day_")?
+                .strip_suffix(';')
+                .map(str::to_owned)
+        },
+    )?;
+    Ok(lines.join("\n") + "\n")
+}
+
+fn register_day(registry: &str, day: u8, padded: &str) -> Result<String, ScaffoldError> {
+    let mut lines = to_lines(registry);
+    let start = lines
+        .iter()
+        .position(|line| line.starts_with("days! {"))
+        .ok_or_else(|| io::Error::other("no `days!` block in the registry"))?
+        + 1;
+    let end = lines[start..]
+        .iter()
+        .position(|line| line == "}")
+        .map_or(lines.len(), |i| start + i);
+    insert_sorted(
+        &mut lines,
+        start..end,
+        format!("    {day} => day_{padded}::Day{padded},"),
+        |line| line.trim().split_once(" =>")?.0.parse::<u8>().ok(),
+    )?;
+    Ok(lines.join("\n") + "\n")
+}
+
+fn register_example(input_rs: &str, day: u8, padded: &str) -> Result<String, ScaffoldError> {
+    let mut lines = to_lines(input_rs);
+    let start = lines
+        .iter()
+        .position(|line| line.contains("const EXAMPLES: "))
+        .ok_or_else(|| io::Error::other("no `EXAMPLES` table in the input tests"))?
+        + 1;
+    let end = lines[start..]
+        .iter()
+        .position(|line| line.trim() == "];")
+        .map_or(lines.len(), |i| start + i);
+    insert_sorted(
+        &mut lines,
+        start..end,
+        format!("        ({day}, day_{padded}::tests::EXAMPLE, &Part::ALL),"),
+        |line| {
+            line.trim()
+                .strip_prefix('(')?
+                .split_once(',')?
+                .0
+                .parse::<u8>()
+                .ok()
+        },
+    )?;
+    Ok(lines.join("\n") + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    struct FakeFetcher;
+
+    impl InputFetcher for FakeFetcher {
+        fn fetch(&self, day: u8) -> io::Result<String> {
+            Ok(format!("input for day {day}\n"))
+        }
+    }
+
+    struct FailingFetcher;
+
+    impl InputFetcher for FailingFetcher {
+        fn fetch(&self, _day: u8) -> io::Result<String> {
+            Err(io::Error::other("no session"))
+        }
+    }
+
+    #[test]
+    fn test() {
+        let root = std::env::temp_dir().join(format!("aoc-scaffold-test-{}", std::process::id()));
+        let scaffold = Scaffold::new(&root);
+        fs::create_dir_all(root.join("src/day_01")).unwrap();
+        fs::write(
+            root.join("src/lib.rs"),
+            "pub mod input;\n\npub mod day_01;\npub mod day_12;\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("src/registry.rs"),
+            indoc! {"
+                days! {
+                    1 => day_01::Day01,
+                    12 => day_12::Day12,
+                }
+
+                pub fn find() {}
+            "},
+        )
+        .unwrap();
+        fs::write(
+            root.join("src/input.rs"),
+            indoc! {"
+                mod tests {
+                    const EXAMPLES: &[(u8, &str, &[Part])] = &[
+                        (1, day_01::tests::EXAMPLE_1, &Part::ALL),
+                        (1, day_01::tests::EXAMPLE_2, &Part::ALL),
+                        // slow
+                        (12, day_12::tests::EXAMPLE, &[Part::One]),
+                    ];
+                }
+            "},
+        )
+        .unwrap();
+
+        let changed = scaffold.new_day(3, Some(&FakeFetcher)).unwrap();
+        assert_eq!(changed.len(), 5);
+        let module = fs::read_to_string(root.join("src/day_03/mod.rs")).unwrap();
+        assert!(module.contains("pub struct Day03;"));
+        assert!(module.contains("impl Solution for Day03 {"));
+        assert!(!module.contains("__DAY"));
+        assert!(module.contains("pub(crate) mod tests {"));
+        assert_eq!(
+            fs::read_to_string(root.join("src/lib.rs")).unwrap(),
+            "pub mod input;\n\npub mod day_01;\npub mod day_03;\npub mod day_12;\n"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("src/registry.rs")).unwrap(),
+            indoc! {"
+                days! {
+                    1 => day_01::Day01,
+                    3 => day_03::Day03,
+                    12 => day_12::Day12,
+                }
+
+                pub fn find() {}
+            "}
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("src/input.rs")).unwrap(),
+            indoc! {"
+                mod tests {
+                    const EXAMPLES: &[(u8, &str, &[Part])] = &[
+                        (1, day_01::tests::EXAMPLE_1, &Part::ALL),
+                        (1, day_01::tests::EXAMPLE_2, &Part::ALL),
+                        (3, day_03::tests::EXAMPLE, &Part::ALL),
+                        // slow
+                        (12, day_12::tests::EXAMPLE, &[Part::One]),
+                    ];
+                }
+            "}
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("input/2023/day3.txt")).unwrap(),
+            "input for day 3\n"
+        );
+
+        scaffold.new_day(13, None).unwrap();
+        assert!(fs::read_to_string(root.join("src/registry.rs"))
+            .unwrap()
+            .contains("    12 => day_12::Day12,\n    13 => day_13::Day13,\n}"));
+        assert!(!root.join("input/2023/day13.txt").exists());
+
+        let lib = fs::read_to_string(root.join("src/lib.rs")).unwrap();
+        assert!(matches!(
+            scaffold.new_day(3, None),
+            Err(ScaffoldError::Exists(_))
+        ));
+        assert!(matches!(
+            scaffold.new_day(1, None),
+            Err(ScaffoldError::Exists(_))
+        ));
+        fs::remove_dir_all(root.join("src/day_12")).ok();
+        assert!(matches!(
+            scaffold.new_day(12, None),
+            Err(ScaffoldError::Exists(_))
+        ));
+        assert_eq!(fs::read_to_string(root.join("src/lib.rs")).unwrap(), lib);
+
+        let registry = fs::read_to_string(root.join("src/registry.rs")).unwrap();
+        let input_rs = fs::read_to_string(root.join("src/input.rs")).unwrap();
+        assert!(matches!(
+            scaffold.new_day(4, Some(&FailingFetcher)),
+            Err(ScaffoldError::Io(_))
+        ));
+        assert!(!root.join("src/day_04").exists());
+        assert!(!root.join("input/2023/day4.txt").exists());
+        assert_eq!(fs::read_to_string(root.join("src/lib.rs")).unwrap(), lib);
+        assert_eq!(
+            fs::read_to_string(root.join("src/registry.rs")).unwrap(),
+            registry
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("src/input.rs")).unwrap(),
+            input_rs
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,199 @@
+use std::{borrow::Cow, fmt};
+
+use crate::{input::normalize, parse::ParseError};
+
+/// The answer to one part of a puzzle, keeping the numeric type the solver produced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    U32(u32),
+    U64(u64),
+    I64(i64),
+    Usize(usize),
+}
+
+impl Answer {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Answer::U32(_) => "u32",
+            Answer::U64(_) => "u64",
+            Answer::I64(_) => "i64",
+            Answer::Usize(_) => "usize",
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::U32(n) => n.fmt(f),
+            Answer::U64(n) => n.fmt(f),
+            Answer::I64(n) => n.fmt(f),
+            Answer::Usize(n) => n.fmt(f),
+        }
+    }
+}
+
+macro_rules! impl_from_for_answer {
+    ($($t:ty => $variant:ident),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::$variant(n)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_for_answer!(u32 => U32, u64 => U64, i64 => I64, usize => Usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+/// Why an input that parsed has no answer, found while solving it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// The answer, or something worked out on the way to it, doesn't fit in 64 bits.
+    Overflow(Cow<'static, str>),
+    /// Something the puzzle relies on isn't in the input.
+    Missing(Cow<'static, str>),
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::Overflow(what) => write!(f, "{what} doesn't fit in 64 bits"),
+            SolveError::Missing(what) => write!(f, "the input has no {what}"),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+/// Why one part of a day has no answer: either the input is malformed at some position, or it
+/// parsed but couldn't be solved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    Parse(ParseError),
+    Solve { day: Option<u8>, error: SolveError },
+}
+
+impl Error {
+    pub fn in_day(self, day: u8) -> Self {
+        match self {
+            Error::Parse(e) => Error::Parse(e.in_day(day)),
+            Error::Solve { error, .. } => Error::Solve {
+                day: Some(day),
+                error,
+            },
+        }
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Self {
+        Error::Parse(e)
+    }
+}
+
+impl From<SolveError> for Error {
+    fn from(error: SolveError) -> Self {
+        Error::Solve { day: None, error }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(e) => e.fmt(f),
+            Error::Solve { day, error } => {
+                if let Some(day) = day {
+                    write!(f, "day {day}: ")?;
+                }
+                error.fmt(f)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A day's puzzle: how to parse the input and how to solve both parts from it.
+pub trait Solution {
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part_1(input: &Self::Input) -> Result<Answer, Error>;
+    fn part_2(input: &Self::Input) -> Result<Answer, Error>;
+}
+
+/// A parsed input with its solver erased, so that days with different input types can share a registry.
+pub trait Parsed {
+    fn part_1(&self) -> Result<Answer, Error>;
+    fn part_2(&self) -> Result<Answer, Error>;
+
+    fn part(&self, part: Part) -> Result<Answer, Error> {
+        match part {
+            Part::One => self.part_1(),
+            Part::Two => self.part_2(),
+        }
+    }
+}
+
+struct ParsedInput<S: Solution> {
+    day: u8,
+    input: S::Input,
+}
+
+impl<S: Solution> Parsed for ParsedInput<S> {
+    fn part_1(&self) -> Result<Answer, Error> {
+        S::part_1(&self.input).map_err(|e| e.in_day(self.day))
+    }
+
+    fn part_2(&self) -> Result<Answer, Error> {
+        S::part_2(&self.input).map_err(|e| e.in_day(self.day))
+    }
+}
+
+type ParseFn = fn(u8, &str) -> Result<Box<dyn Parsed>, ParseError>;
+
+fn parse_boxed<S: Solution + 'static>(day: u8, input: &str) -> Result<Box<dyn Parsed>, ParseError> {
+    match S::parse(input) {
+        Ok(input) => Ok(Box::new(ParsedInput::<S> { day, input })),
+        Err(e) => Err(e.in_day(day)),
+    }
+}
+
+/// An entry in the registry of days.
+pub struct Day {
+    pub number: u8,
+    parse: ParseFn,
+}
+
+impl Day {
+    pub const fn new<S: Solution + 'static>(number: u8) -> Self {
+        Day {
+            number,
+            parse: parse_boxed::<S>,
+        }
+    }
+
+    /// Parses an input after [normalizing](crate::input::normalize) it.
+    pub fn parse(&self, input: &str) -> Result<Box<dyn Parsed>, ParseError> {
+        (self.parse)(self.number, &normalize(input))
+    }
+}